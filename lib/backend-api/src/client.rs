@@ -1,7 +1,6 @@
-#[cfg(not(target_family = "wasm"))]
 use std::time::Duration;
 
-use crate::GraphQLApiFailure;
+use crate::{retry::RetryPolicy, GraphQLApiFailure};
 use anyhow::{bail, Context as _};
 use cynic::{http::CynicReqwestError, GraphQlResponse, Operation};
 #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
@@ -22,6 +21,120 @@ pub struct WasmerClient {
     pub(crate) user_agent: reqwest::header::HeaderValue,
     #[allow(unused)]
     log_variables: bool,
+    idempotency_keys: bool,
+    retry_policy: RetryPolicy,
+}
+
+/// Builder for a [`WasmerClient`] with custom HTTP settings.
+///
+/// Settings that are not specified keep the same defaults as
+/// [`WasmerClient::new`].
+///
+/// NOTE: timeouts, pool and keep-alive settings are ignored on
+/// `wasm32-unknown-unknown`, where the browser manages connections.
+pub struct WasmerClientBuilder {
+    graphql_endpoint: Url,
+    user_agent: String,
+    #[allow(unused)]
+    proxy: Option<Proxy>,
+    #[allow(unused)]
+    connect_timeout: Duration,
+    #[allow(unused)]
+    timeout: Duration,
+    #[allow(unused)]
+    pool_max_idle_per_host: Option<usize>,
+    #[allow(unused)]
+    tcp_keepalive: Option<Duration>,
+    retry_policy: RetryPolicy,
+}
+
+impl WasmerClientBuilder {
+    /// The default timeout for establishing a connection.
+    pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+    /// The default timeout for a whole request.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(90);
+
+    pub fn new(graphql_endpoint: Url, user_agent: &str) -> Self {
+        Self {
+            graphql_endpoint,
+            user_agent: user_agent.to_string(),
+            proxy: None,
+            connect_timeout: Self::DEFAULT_CONNECT_TIMEOUT,
+            timeout: Self::DEFAULT_TIMEOUT,
+            pool_max_idle_per_host: None,
+            tcp_keepalive: None,
+            retry_policy: RetryPolicy::NONE,
+        }
+    }
+
+    /// Route all requests through the given proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Set the timeout for a whole request, from connecting until the response
+    /// body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept alive per host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Set the interval of TCP keep-alive probes.
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Set how requests that failed with a transient error are retried.
+    ///
+    /// Queries are always retried, mutations only if the client sends
+    /// idempotency keys (see [`WasmerClient::with_idempotency_keys`]).
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn build(self) -> Result<WasmerClient, anyhow::Error> {
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        let builder = reqwest::ClientBuilder::new();
+
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        let builder = {
+            let mut builder = reqwest::ClientBuilder::new()
+                .connect_timeout(self.connect_timeout)
+                .timeout(self.timeout)
+                .tcp_keepalive(self.tcp_keepalive);
+            if let Some(max) = self.pool_max_idle_per_host {
+                builder = builder.pool_max_idle_per_host(max);
+            }
+            if let Some(proxy) = self.proxy {
+                builder = builder.proxy(proxy);
+            }
+            builder
+        };
+
+        let client = builder.build().context("failed to create reqwest client")?;
+
+        let client =
+            WasmerClient::new_with_client(client, self.graphql_endpoint, &self.user_agent)?;
+        Ok(WasmerClient {
+            retry_policy: self.retry_policy,
+            ..client
+        })
+    }
 }
 
 impl WasmerClient {
@@ -31,6 +144,11 @@ impl WasmerClient {
     /// it is gated by a custom env var.
     const ENV_VAR_LOG_VARIABLES: &'static str = "WASMER_API_INSECURE_LOG_VARIABLES";
 
+    /// The HTTP header used to send the idempotency key of mutations.
+    ///
+    /// See [`Self::with_idempotency_keys`].
+    pub const IDEMPOTENCY_KEY_HEADER: &'static str = "Idempotency-Key";
+
     pub fn graphql_endpoint(&self) -> &Url {
         &self.graphql_endpoint
     }
@@ -76,6 +194,8 @@ impl WasmerClient {
             user_agent: Self::parse_user_agent(user_agent)?,
             graphql_endpoint,
             log_variables,
+            idempotency_keys: false,
+            retry_policy: RetryPolicy::NONE,
         })
     }
 
@@ -88,28 +208,19 @@ impl WasmerClient {
         user_agent: &str,
         proxy: Option<Proxy>,
     ) -> Result<Self, anyhow::Error> {
-        let builder = {
-            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
-            let mut builder = reqwest::ClientBuilder::new();
-
-            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
-            let builder = reqwest::ClientBuilder::new()
-                .connect_timeout(Duration::from_secs(10))
-                .timeout(Duration::from_secs(90));
-
-            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
-            if let Some(proxy) = proxy {
-                builder.proxy(proxy)
-            } else {
-                builder
-            }
-            #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        let builder = WasmerClientBuilder::new(graphql_endpoint, user_agent);
+        let builder = if let Some(proxy) = proxy {
+            builder.proxy(proxy)
+        } else {
             builder
         };
 
-        let client = builder.build().context("failed to create reqwest client")?;
+        builder.build()
+    }
 
-        Self::new_with_client(client, graphql_endpoint, user_agent)
+    /// Create a [`WasmerClientBuilder`] to customize the HTTP settings of the client.
+    pub fn builder(graphql_endpoint: Url, user_agent: &str) -> WasmerClientBuilder {
+        WasmerClientBuilder::new(graphql_endpoint, user_agent)
     }
 
     pub fn with_auth_token(mut self, auth_token: String) -> Self {
@@ -117,6 +228,74 @@ impl WasmerClient {
         self
     }
 
+    /// Send an idempotency key with every mutation sent by this client, which also makes
+    /// mutations eligible for retries (see [`WasmerClientBuilder::retry_policy`]).
+    ///
+    /// Every mutation gets a new random key in the [`Self::IDEMPOTENCY_KEY_HEADER`]
+    /// header, which is only reused when that mutation is retried: two identical
+    /// mutations sent on purpose are never mistaken for a retry. Queries never
+    /// carry the header.
+    ///
+    /// NOTE: the key only makes retries safe if the backend deduplicates
+    /// requests based on it; servers that do not know the header ignore it.
+    pub fn with_idempotency_keys(mut self) -> Self {
+        self.idempotency_keys = true;
+        self
+    }
+
+    /// Generate the idempotency key for a new operation, if any.
+    fn idempotency_key_for(&self, query: &str) -> Option<String> {
+        (self.idempotency_keys && is_mutation(query)).then(|| uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Send a single GraphQL request and decode its response.
+    async fn send_graphql<ResponseData>(
+        req: reqwest::RequestBuilder,
+    ) -> Result<GraphQlResponse<ResponseData>, CynicReqwestError>
+    where
+        ResponseData: serde::de::DeserializeOwned + 'static,
+    {
+        match req.send().await {
+            Ok(response) => {
+                let status = response.status();
+                if !status.is_success() {
+                    let body_string = match response.text().await {
+                        Ok(b) => b,
+                        Err(err) => {
+                            tracing::error!("could not load response body: {err}");
+                            "<could not retrieve body>".to_string()
+                        }
+                    };
+
+                    match serde_json::from_str::<GraphQlResponse<ResponseData>>(&body_string) {
+                        Ok(response) => Ok(response),
+                        Err(_) => Err(CynicReqwestError::ErrorResponse(status, body_string)),
+                    }
+                } else {
+                    let body = response.bytes().await?;
+
+                    let jd = &mut serde_json::Deserializer::from_slice(&body);
+                    let data: Result<GraphQlResponse<ResponseData>, _> =
+                        serde_path_to_error::deserialize(jd).map_err(|err| {
+                            // The body can contain secret values, so it is only
+                            // logged at the trace level, and not part of the error.
+                            tracing::trace!(
+                                body = %String::from_utf8_lossy(&body),
+                                "could not decode the GraphQL response"
+                            );
+                            CynicReqwestError::ErrorResponse(
+                                reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+                                format!("Could not decode JSON response: {err}"),
+                            )
+                        });
+
+                    data
+                }
+            }
+            Err(e) => Err(CynicReqwestError::ReqwestError(e)),
+        }
+    }
+
     pub(crate) async fn run_graphql_raw<ResponseData, Vars>(
         &self,
         operation: Operation<ResponseData, Vars>,
@@ -134,6 +313,19 @@ impl WasmerClient {
         } else {
             req
         };
+        // Generated once, so that all attempts below send the same key.
+        let idempotency_key = self.idempotency_key_for(&operation.query);
+        // Retrying a mutation is only safe if the backend can deduplicate it.
+        let retry_policy = if idempotency_key.is_some() || !is_mutation(&operation.query) {
+            self.retry_policy
+        } else {
+            RetryPolicy::NONE
+        };
+        let req = if let Some(key) = idempotency_key {
+            req.header(Self::IDEMPOTENCY_KEY_HEADER, key)
+        } else {
+            req
+        };
 
         let query = operation.query.clone();
 
@@ -152,58 +344,35 @@ impl WasmerClient {
             );
         }
 
-        let res = req.json(&operation).send().await;
-
-        let res = match res {
-            Ok(response) => {
-                let status = response.status();
-                if !status.is_success() {
-                    let body_string = match response.text().await {
-                        Ok(b) => b,
-                        Err(err) => {
-                            tracing::error!("could not load response body: {err}");
-                            "<could not retrieve body>".to_string()
-                        }
-                    };
-
-                    match serde_json::from_str::<GraphQlResponse<ResponseData>>(&body_string) {
-                        Ok(response) => Ok(response),
-                        Err(_) => Err(CynicReqwestError::ErrorResponse(status, body_string)),
-                    }
-                } else {
-                    let body = response.bytes().await?;
-
-                    let jd = &mut serde_json::Deserializer::from_slice(&body);
-                    let data: Result<GraphQlResponse<ResponseData>, _> =
-                        serde_path_to_error::deserialize(jd).map_err(|err| {
-                            let body_txt = String::from_utf8_lossy(&body);
-                            CynicReqwestError::ErrorResponse(
-                                reqwest::StatusCode::INTERNAL_SERVER_ERROR,
-                                format!("Could not decode JSON response: {err} -- '{body_txt}'"),
-                            )
-                        });
-
-                    data
-                }
-            }
-            Err(e) => Err(CynicReqwestError::ReqwestError(e)),
-        };
+        let res = crate::retry::retry(retry_policy, || async {
+            let req = req
+                .try_clone()
+                .expect("requests without a body can always be cloned");
+            // Held until the response has been read, see `WASMER_MAX_CONCURRENCY`.
+            let _permit = crate::concurrency::acquire().await;
+            Self::send_graphql::<ResponseData>(req.json(&operation)).await
+        })
+        .await;
         let res = match res {
             Ok(res) => {
                 tracing::trace!(?res, "GraphQL query succeeded");
                 res
             }
             Err(err) => {
-                tracing::error!(?err, "GraphQL query failed");
-                return Err(err.into());
+                // Errors can carry the response body, which is only logged at
+                // the trace level, like the responses themselves.
+                tracing::error!(endpoint=%self.graphql_endpoint, %query, "GraphQL query failed");
+                tracing::trace!(?err, "GraphQL query failed");
+                return Err(err);
             }
         };
 
         if let Some(errors) = &res.errors {
             if !errors.is_empty() {
+                // Without the data: a partial response still holds the values
+                // of the fields that did not fail.
                 tracing::warn!(
                     ?errors,
-                    data=?res.data,
                     %query,
                     endpoint=%self.graphql_endpoint,
                     "GraphQL query succeeded, but returned errors",
@@ -260,3 +429,116 @@ impl WasmerClient {
         }
     }
 }
+
+fn is_mutation(query: &str) -> bool {
+    query.trim_start().starts_with("mutation")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// A minimal HTTP server that answers one request with each of the given status codes and
+    /// GraphQL response bodies, returning the value of the idempotency key header of each request.
+    async fn mock_server(
+        responses: &'static [(u16, &'static str)],
+    ) -> (Url, tokio::task::JoinHandle<Vec<Option<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/graphql",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+
+        let handle = tokio::spawn(async move {
+            let mut keys = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (head, body_len) = loop {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&buf).to_string();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let head = text[..end].to_lowercase();
+                        let len = head
+                            .lines()
+                            .find_map(|l| l.strip_prefix("content-length:"))
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                            .unwrap_or_default();
+                        break (head, end + 4 + len);
+                    }
+                };
+                while buf.len() < body_len {
+                    let n = stream.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+
+                let header = format!("{}:", WasmerClient::IDEMPOTENCY_KEY_HEADER.to_lowercase());
+                keys.push(
+                    head.lines()
+                        .find_map(|l| l.strip_prefix(header.as_str()))
+                        .map(|v| v.trim().to_string()),
+                );
+
+                let response = format!(
+                    "HTTP/1.1 {status} Status\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            keys
+        });
+
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_is_sent_for_mutations() {
+        const OK: &str = r#"{"data":{"deleteAppSecret":null}}"#;
+        let (url, server) = mock_server(&[(503, "unavailable"), (200, OK), (200, OK)]).await;
+        let client = WasmerClient::builder(url, "test")
+            .retry_policy(RetryPolicy {
+                max_attempts: 2,
+                backoff: Duration::ZERO,
+            })
+            .build()
+            .unwrap()
+            .with_idempotency_keys();
+
+        // The first attempt fails and is retried.
+        crate::query::delete_app_secret(&client, "secret-a")
+            .await
+            .unwrap();
+        crate::query::delete_app_secret(&client, "secret-a")
+            .await
+            .unwrap();
+
+        let keys = server.await.unwrap();
+        let keys: Vec<String> = keys.into_iter().map(Option::unwrap).collect();
+        // Retrying a mutation reuses its key...
+        assert_eq!(keys[0], keys[1]);
+        // ... but sending the same mutation again is a new operation.
+        assert_ne!(keys[1], keys[2]);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_is_not_sent_for_queries() {
+        let (url, server) = mock_server(&[(200, r#"{"data":{"getAppSecret":null}}"#)]).await;
+        let client = WasmerClient::new(url, "test")
+            .unwrap()
+            .with_idempotency_keys();
+
+        crate::query::get_app_secret_by_name(&client, "app", "NAME")
+            .await
+            .unwrap();
+
+        assert_eq!(server.await.unwrap(), vec![None]);
+    }
+}
@@ -0,0 +1,73 @@
+//! A process-wide cap on the number of concurrent API requests.
+//!
+//! The cap is read once from the [`MAX_CONCURRENCY_ENV`] environment variable.
+//! Every request sent by a [`crate::WasmerClient`] holds a permit of a shared
+//! semaphore while it is in flight, so the cap holds across all clients and
+//! commands, whatever concurrency they ask for.
+
+use std::sync::OnceLock;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// The environment variable that limits the number of concurrent API requests.
+pub const MAX_CONCURRENCY_ENV: &str = "WASMER_MAX_CONCURRENCY";
+
+/// The limit set with [`MAX_CONCURRENCY_ENV`], if any.
+///
+/// Invalid values (not a positive integer) are ignored with a warning.
+pub fn max_concurrency() -> Option<usize> {
+    static MAX: OnceLock<Option<usize>> = OnceLock::new();
+    *MAX.get_or_init(|| {
+        let value = std::env::var(MAX_CONCURRENCY_ENV).ok()?;
+        match parse_max_concurrency(&value) {
+            Some(max) => Some(max),
+            None => {
+                tracing::warn!(
+                    value,
+                    "ignoring {MAX_CONCURRENCY_ENV}: expected a positive integer"
+                );
+                None
+            }
+        }
+    })
+}
+
+fn parse_max_concurrency(value: &str) -> Option<usize> {
+    value.trim().parse().ok().filter(|max| *max > 0)
+}
+
+/// The concurrency to use for an operation that asks for `requested`
+/// concurrent requests: the smaller of `requested` and [`max_concurrency`].
+pub fn effective_concurrency(requested: usize) -> usize {
+    match max_concurrency() {
+        Some(max) => requested.min(max),
+        None => requested,
+    }
+    .max(1)
+}
+
+/// Wait for a permit to send a request.
+///
+/// Returns `None` if there is no limit.
+pub(crate) async fn acquire() -> Option<SemaphorePermit<'static>> {
+    static SEMAPHORE: OnceLock<Option<Semaphore>> = OnceLock::new();
+    let semaphore = SEMAPHORE
+        .get_or_init(|| max_concurrency().map(Semaphore::new))
+        .as_ref()?;
+    // The semaphore is never closed.
+    semaphore.acquire().await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_max_concurrency() {
+        assert_eq!(parse_max_concurrency("4"), Some(4));
+        assert_eq!(parse_max_concurrency(" 16\n"), Some(16));
+        assert_eq!(parse_max_concurrency("0"), None);
+        assert_eq!(parse_max_concurrency("-1"), None);
+        assert_eq!(parse_max_concurrency("many"), None);
+    }
+}
@@ -4,15 +4,23 @@
 mod client;
 mod error;
 
+pub mod concurrency;
 pub mod global_id;
+#[cfg(feature = "test-util")]
+pub mod mock;
 pub mod query;
+pub mod retry;
+pub mod secrets;
 pub mod stream;
 pub mod subscription;
 pub mod types;
 
 use url::Url;
 
-pub use self::{client::WasmerClient, error::GraphQLApiFailure};
+pub use self::{
+    client::{WasmerClient, WasmerClientBuilder},
+    error::GraphQLApiFailure,
+};
 
 /// Api endpoint for the dev environment.
 pub const ENDPOINT_DEV: &str = "https://registry.wasmer.wtf/graphql";
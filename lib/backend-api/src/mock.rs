@@ -0,0 +1,185 @@
+//! In-memory implementations of the API traits, for tests.
+//!
+//! Only available with the `test-util` feature.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    secrets::SecretsApi,
+    types::{DateTime, DeploySecretLogActionChoices, Secret, SecretLog},
+};
+
+/// The timestamp used for the `created_at` and `updated_at` fields of mocked
+/// secrets.
+const MOCK_TIMESTAMP: &str = "2024-01-01T00:00:00+00:00";
+
+#[derive(Debug, Clone)]
+struct MockSecret {
+    id: String,
+    name: String,
+    /// The value of the secret, or the error returned when fetching it.
+    value: Result<String, String>,
+}
+
+impl MockSecret {
+    fn to_secret(&self) -> Secret {
+        Secret {
+            id: cynic::Id::new(&self.id),
+            name: self.name.clone(),
+            created_at: DateTime(MOCK_TIMESTAMP.to_string()),
+            updated_at: DateTime(MOCK_TIMESTAMP.to_string()),
+        }
+    }
+}
+
+/// A [`SecretsApi`] serving canned secrets from memory.
+///
+/// ```
+/// # use wasmer_backend_api::{mock::MockSecretsApi, secrets::SecretsApi};
+/// # futures::executor::block_on(async {
+/// let api = MockSecretsApi::new()
+///     .with_secret("da_123", "DB_URL", "postgres://...")
+///     .with_failing_secret("da_123", "API_KEY", "permission denied");
+///
+/// let secrets = api.get_all_app_secrets("da_123").await.unwrap();
+/// assert_eq!(secrets.len(), 2);
+/// # });
+/// ```
+#[derive(Debug, Default)]
+pub struct MockSecretsApi {
+    apps: HashMap<String, Vec<MockSecret>>,
+    logs: HashMap<String, Vec<SecretLog>>,
+    /// The largest page of the secret log returned, whatever the number of
+    /// events asked for.
+    max_log_page_size: Option<usize>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockSecretsApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a secret with the given value to an app.
+    pub fn with_secret(self, app_id: &str, name: &str, value: &str) -> Self {
+        self.push(app_id, name, Ok(value.to_string()))
+    }
+
+    /// Add a secret to an app, whose value cannot be fetched: listing the
+    /// secret succeeds, but fetching its value fails with the given error.
+    pub fn with_failing_secret(self, app_id: &str, name: &str, error: &str) -> Self {
+        self.push(app_id, name, Err(error.to_string()))
+    }
+
+    fn push(mut self, app_id: &str, name: &str, value: Result<String, String>) -> Self {
+        let secrets = self.apps.entry(app_id.to_string()).or_default();
+        let id = format!("{app_id}/secret-{}", secrets.len());
+        secrets.push(MockSecret {
+            id,
+            name: name.to_string(),
+            value,
+        });
+        self
+    }
+
+    /// Add an event to the secret log of an app. Events are returned in the
+    /// order they were added.
+    pub fn with_log_event(
+        mut self,
+        app_id: &str,
+        secret_name: &str,
+        action: DeploySecretLogActionChoices,
+    ) -> Self {
+        let log = self.logs.entry(app_id.to_string()).or_default();
+        log.push(SecretLog {
+            id: cynic::Id::new(format!("{app_id}/log-{}", log.len())),
+            secret_name: secret_name.to_string(),
+            action,
+            created_at: DateTime(MOCK_TIMESTAMP.to_string()),
+        });
+        self
+    }
+
+    /// Return at most `size` events per page of the secret log, like a server
+    /// capping the page size.
+    pub fn with_max_log_page_size(mut self, size: usize) -> Self {
+        self.max_log_page_size = Some(size);
+        self
+    }
+
+    /// The calls made so far, as `<method>(<arguments>)`, in order.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: String) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn find_by_id(&self, secret_id: &str) -> Option<&MockSecret> {
+        self.apps.values().flatten().find(|s| s.id == secret_id)
+    }
+}
+
+#[async_trait::async_trait]
+impl SecretsApi for MockSecretsApi {
+    async fn get_all_app_secrets(&self, app_id: &str) -> Result<Vec<Secret>, anyhow::Error> {
+        self.record(format!("get_all_app_secrets({app_id})"));
+        let secrets = self
+            .apps
+            .get(app_id)
+            .ok_or_else(|| anyhow::anyhow!("app not found"))?;
+        Ok(secrets.iter().map(MockSecret::to_secret).collect())
+    }
+
+    async fn get_app_secret_by_name(
+        &self,
+        app_id: &str,
+        name: &str,
+    ) -> Result<Option<Secret>, anyhow::Error> {
+        self.record(format!("get_app_secret_by_name({app_id}, {name})"));
+        Ok(self
+            .apps
+            .get(app_id)
+            .and_then(|secrets| secrets.iter().find(|s| s.name == name))
+            .map(MockSecret::to_secret))
+    }
+
+    async fn get_app_secret_value_by_id(
+        &self,
+        secret_id: &str,
+    ) -> Result<Option<String>, anyhow::Error> {
+        self.record(format!("get_app_secret_value_by_id({secret_id})"));
+        match self.find_by_id(secret_id).map(|s| &s.value) {
+            Some(Ok(value)) => Ok(Some(value.clone())),
+            Some(Err(error)) => Err(anyhow::anyhow!("{error}")),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_app_secret_log_page(
+        &self,
+        app_id: &str,
+        after: Option<String>,
+        first: i32,
+    ) -> Result<(Vec<SecretLog>, Option<String>), anyhow::Error> {
+        self.record(format!(
+            "get_app_secret_log_page({app_id}, {})",
+            after.as_deref().unwrap_or("-")
+        ));
+        let log = self
+            .logs
+            .get(app_id)
+            .ok_or_else(|| anyhow::anyhow!("app not found"))?;
+
+        // The cursor of an event is its index in the log.
+        let start = match after {
+            Some(cursor) => cursor.parse::<usize>()? + 1,
+            None => 0,
+        };
+        let size = usize::try_from(first)?.min(self.max_log_page_size.unwrap_or(usize::MAX));
+        let page: Vec<_> = log.iter().skip(start).take(size).cloned().collect();
+        let cursor = page.len().checked_sub(1).map(|i| (start + i).to_string());
+        Ok((page, cursor))
+    }
+}
@@ -392,6 +392,38 @@ pub async fn get_app_secrets(
     res.get_app_secrets.context("app not found")
 }
 
+/// Load a page of at most `first` events of the secret log (access, creation,
+/// modification and deletion events) of an app, starting after the `after` cursor.
+///
+/// Returns the events and the cursor of the last one, or `None` if the page is
+/// empty. The log never contains secret values.
+///
+/// See [`crate::secrets::app_secret_log`] to stream all pages.
+pub async fn get_app_secret_log_page(
+    client: &WasmerClient,
+    app_id: impl Into<String>,
+    after: Option<String>,
+    first: i32,
+) -> Result<(Vec<SecretLog>, Option<String>), anyhow::Error> {
+    let page = client
+        .run_graphql_strict(types::GetAppSecretLog::build(GetAppSecretLogVariables {
+            app_id: types::Id::from(app_id.into()),
+            after,
+            first: Some(first),
+        }))
+        .await?
+        .get_app_secret_log
+        .context("app not found")?;
+
+    let mut events = Vec::new();
+    let mut cursor = None;
+    for edge in page.edges.into_iter().flatten() {
+        cursor = Some(edge.cursor);
+        events.extend(edge.node);
+    }
+    Ok((events, cursor))
+}
+
 pub async fn delete_app_secret(
     client: &WasmerClient,
     secret_id: impl Into<String>,
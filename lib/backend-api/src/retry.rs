@@ -0,0 +1,183 @@
+//! Retries of API requests that failed with a transient error.
+
+use std::{future::Future, time::Duration};
+
+use cynic::http::CynicReqwestError;
+
+/// How often, and how fast, failed requests are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The delay before the first retry, doubled after every attempt.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Never retry.
+    pub const NONE: Self = Self {
+        max_attempts: 1,
+        backoff: Duration::ZERO,
+    };
+
+    /// Retry up to `retries` times, starting with a delay of 500ms.
+    pub fn with_retries(retries: u32) -> Self {
+        Self {
+            max_attempts: retries.saturating_add(1),
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// Whether a request that failed with `err` may succeed if sent again.
+fn is_transient(err: &CynicReqwestError) -> bool {
+    match err {
+        CynicReqwestError::ReqwestError(err) => err.is_timeout() || err.is_connect(),
+        CynicReqwestError::ErrorResponse(status, _) => {
+            status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        }
+    }
+}
+
+/// A short description of `err`, for the history of failed attempts.
+///
+/// Only the kind of error is kept: response bodies could echo the request, and
+/// thus secret values, so they are left out.
+fn summarize(err: &CynicReqwestError) -> String {
+    match err {
+        CynicReqwestError::ReqwestError(err) if err.is_timeout() => "timeout".to_string(),
+        CynicReqwestError::ReqwestError(err) if err.is_connect() => "connection error".to_string(),
+        CynicReqwestError::ReqwestError(err) => match err.status() {
+            Some(status) => status.as_u16().to_string(),
+            None => "request error".to_string(),
+        },
+        CynicReqwestError::ErrorResponse(status, _) => status.as_u16().to_string(),
+    }
+}
+
+/// The errors seen over all attempts, rendered as e.g. `3 attempts: [timeout, 502 (x2)]`.
+///
+/// Consecutive identical errors are collapsed.
+fn describe_history(history: &[String]) -> String {
+    let mut groups: Vec<(&str, usize)> = Vec::new();
+    for err in history {
+        match groups.last_mut() {
+            Some((last, count)) if *last == err.as_str() => *count += 1,
+            _ => groups.push((err, 1)),
+        }
+    }
+
+    let mut out = format!("{} attempts: [", history.len());
+    for (i, (err, count)) in groups.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(err);
+        if *count > 1 {
+            out.push_str(&format!(" (x{count})"));
+        }
+    }
+    out.push(']');
+    out
+}
+
+/// Run `op` until it succeeds, fails with an error that is not transient, or
+/// `policy.max_attempts` is reached.
+///
+/// If more than one attempt was made, the returned error carries the history of
+/// all failed attempts as context.
+pub(crate) async fn retry<T, F, Fut>(policy: RetryPolicy, mut op: F) -> Result<T, anyhow::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, CynicReqwestError>>,
+{
+    let mut history = Vec::new();
+    let mut backoff = policy.backoff;
+
+    loop {
+        let err = match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        history.push(summarize(&err));
+
+        let attempts = history.len() as u32;
+        if attempts >= policy.max_attempts || !is_transient(&err) {
+            let err = anyhow::Error::from(err);
+            return Err(if attempts > 1 {
+                err.context(describe_history(&history))
+            } else {
+                err
+            });
+        }
+
+        tracing::debug!(
+            attempt = attempts,
+            error = %history[history.len() - 1],
+            "request failed, retrying"
+        );
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    fn response(status: u16) -> CynicReqwestError {
+        CynicReqwestError::ErrorResponse(
+            StatusCode::from_u16(status).unwrap(),
+            "SECRET_VALUE".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_describe_history() {
+        let history = ["timeout", "502", "502"].map(String::from);
+        assert_eq!(
+            describe_history(&history),
+            "3 attempts: [timeout, 502 (x2)]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhausted() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::ZERO,
+        };
+        let mut statuses = vec![503, 500, 502];
+        let err = retry(policy, || {
+            let status = statuses.remove(0);
+            async move { Err::<(), _>(response(status)) }
+        })
+        .await
+        .unwrap_err();
+
+        let msg = format!("{err:#}");
+        assert!(msg.starts_with("3 attempts: [503, 500, 502]"), "{msg}");
+        assert!(!err.to_string().contains("SECRET_VALUE"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_on_permanent_errors() {
+        let mut attempts = 0;
+        let err = retry(RetryPolicy::with_retries(3), || {
+            attempts += 1;
+            async { Err::<(), _>(response(400)) }
+        })
+        .await
+        .unwrap_err();
+
+        assert_eq!(attempts, 1);
+        assert!(!format!("{err:#}").contains("attempts"));
+    }
+}
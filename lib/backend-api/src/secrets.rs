@@ -0,0 +1,105 @@
+//! A trait for the secret-related parts of the API, so that code using them
+//! can be tested without a live backend.
+//!
+//! With the `test-util` feature, [`crate::mock::MockSecretsApi`] provides an
+//! in-memory implementation.
+
+use std::time::Duration;
+
+use crate::{
+    types::{Secret, SecretLog},
+    WasmerClient,
+};
+
+/// The number of events requested per page of the secret log.
+const SECRET_LOG_PAGE_SIZE: i32 = 100;
+
+/// The API calls used to read the secrets of an app.
+///
+/// Implemented by [`WasmerClient`] by forwarding to the matching functions in
+/// [`crate::query`].
+#[async_trait::async_trait]
+pub trait SecretsApi: Send + Sync {
+    /// Load all secrets of an app. See [`crate::query::get_all_app_secrets`].
+    async fn get_all_app_secrets(&self, app_id: &str) -> Result<Vec<Secret>, anyhow::Error>;
+
+    /// Load a secret of an app by name. See [`crate::query::get_app_secret_by_name`].
+    async fn get_app_secret_by_name(
+        &self,
+        app_id: &str,
+        name: &str,
+    ) -> Result<Option<Secret>, anyhow::Error>;
+
+    /// Load the value of a secret. See [`crate::query::get_app_secret_value_by_id`].
+    async fn get_app_secret_value_by_id(
+        &self,
+        secret_id: &str,
+    ) -> Result<Option<String>, anyhow::Error>;
+
+    /// Load a page of the secret log of an app. See [`crate::query::get_app_secret_log_page`].
+    async fn get_app_secret_log_page(
+        &self,
+        app_id: &str,
+        after: Option<String>,
+        first: i32,
+    ) -> Result<(Vec<SecretLog>, Option<String>), anyhow::Error>;
+}
+
+#[async_trait::async_trait]
+impl SecretsApi for WasmerClient {
+    async fn get_all_app_secrets(&self, app_id: &str) -> Result<Vec<Secret>, anyhow::Error> {
+        crate::query::get_all_app_secrets(self, app_id).await
+    }
+
+    async fn get_app_secret_by_name(
+        &self,
+        app_id: &str,
+        name: &str,
+    ) -> Result<Option<Secret>, anyhow::Error> {
+        crate::query::get_app_secret_by_name(self, app_id, name).await
+    }
+
+    async fn get_app_secret_value_by_id(
+        &self,
+        secret_id: &str,
+    ) -> Result<Option<String>, anyhow::Error> {
+        crate::query::get_app_secret_value_by_id(self, secret_id).await
+    }
+
+    async fn get_app_secret_log_page(
+        &self,
+        app_id: &str,
+        after: Option<String>,
+        first: i32,
+    ) -> Result<(Vec<SecretLog>, Option<String>), anyhow::Error> {
+        crate::query::get_app_secret_log_page(self, app_id, after, first).await
+    }
+}
+
+/// Stream the secret log of an app, one non-empty page at a time.
+///
+/// Without `follow`, the stream ends once all events were returned. With
+/// `follow`, it polls for new events every `poll_interval` instead.
+pub fn app_secret_log<'a, A: SecretsApi + ?Sized>(
+    api: &'a A,
+    app_id: &'a str,
+    follow: bool,
+    poll_interval: Duration,
+) -> impl futures::Stream<Item = Result<Vec<SecretLog>, anyhow::Error>> + 'a {
+    futures::stream::try_unfold(None::<String>, move |mut after| async move {
+        loop {
+            let (events, cursor) = api
+                .get_app_secret_log_page(app_id, after.clone(), SECRET_LOG_PAGE_SIZE)
+                .await?;
+            after = cursor.or(after);
+
+            if !events.is_empty() {
+                break Ok(Some((events, after)));
+            }
+            if !follow {
+                break Ok(None);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    })
+}
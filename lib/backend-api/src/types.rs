@@ -1508,6 +1508,49 @@ mod queries {
         pub get_app_secret: Option<Secret>,
     }
 
+    #[derive(cynic::QueryVariables, Debug, Clone)]
+    pub struct GetAppSecretLogVariables {
+        pub app_id: cynic::Id,
+        pub after: Option<String>,
+        pub first: Option<i32>,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    #[cynic(graphql_type = "Query", variables = "GetAppSecretLogVariables")]
+    pub struct GetAppSecretLog {
+        #[arguments(appId: $app_id, after: $after, first: $first)]
+        pub get_app_secret_log: Option<SecretLogConnection>,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    pub struct SecretLogConnection {
+        pub edges: Vec<Option<SecretLogEdge>>,
+        pub page_info: PageInfo,
+    }
+
+    #[derive(cynic::QueryFragment, Debug)]
+    pub struct SecretLogEdge {
+        pub cursor: String,
+        pub node: Option<SecretLog>,
+    }
+
+    #[derive(cynic::QueryFragment, Debug, Clone, Serialize)]
+    pub struct SecretLog {
+        #[serde(skip_serializing)]
+        pub id: cynic::Id,
+        pub secret_name: String,
+        pub action: DeploySecretLogActionChoices,
+        pub created_at: DateTime,
+    }
+
+    #[derive(cynic::Enum, Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum DeploySecretLogActionChoices {
+        Access,
+        Modification,
+        Create,
+        Delete,
+    }
+
     #[derive(cynic::QueryVariables, Debug)]
     pub struct GetAppSecretValueVariables {
         pub id: cynic::Id,
@@ -42,7 +42,7 @@ impl AsyncCliCommand for CmdAppDeploymentList {
         if items.is_empty() {
             eprintln!("App {} has no deployments!", app.name);
         } else {
-            println!("{}", self.fmt.format.render(&items));
+            println!("{}", self.fmt.get(&self.env).render(&items));
         }
 
         Ok(())
@@ -90,7 +90,7 @@ impl AsyncCliCommand for CmdAppList {
             rem -= limit;
 
             if self.paging_mode {
-                println!("{}", self.fmt.format.render(&apps));
+                println!("{}", self.fmt.get(&self.env).render(&apps));
 
                 loop {
                     println!("next page? [y, n]");
@@ -113,7 +113,7 @@ impl AsyncCliCommand for CmdAppList {
         }
 
         if !display_apps.is_empty() {
-            println!("{}", self.fmt.format.render(&display_apps));
+            println!("{}", self.fmt.get(&self.env).render(&display_apps));
         }
 
         Ok(())
@@ -83,6 +83,7 @@ impl crate::commands::AsyncCliCommand for CmdAppLogs {
 
     async fn run_async(self) -> Result<(), anyhow::Error> {
         let client = self.env.client()?;
+        let format = self.fmt.get(&self.env);
 
         let (_ident, app) = self.ident.load_app(&client).await?;
 
@@ -151,7 +152,7 @@ impl crate::commands::AsyncCliCommand for CmdAppLogs {
                 let logs: Vec<_> = logs.drain(..limit).collect();
 
                 if !logs.is_empty() {
-                    let rendered = self.fmt.format.render(&logs);
+                    let rendered = format.render(&logs);
                     println!("{rendered}");
 
                     rem -= limit;
@@ -186,7 +187,7 @@ impl crate::commands::AsyncCliCommand for CmdAppLogs {
                 let logs: Vec<_> = logs.drain(..limit).collect();
 
                 if !logs.is_empty() {
-                    let rendered = self.fmt.format.render(&logs);
+                    let rendered = format.render(&logs);
                     println!("{rendered}");
 
                     rem -= limit;
@@ -220,7 +221,7 @@ impl crate::commands::AsyncCliCommand for CmdAppLogs {
                 let logs: Vec<_> = logs.drain(..limit).collect();
 
                 if !logs.is_empty() {
-                    let rendered = self.fmt.format.render(&logs);
+                    let rendered = format.render(&logs);
                     println!("{rendered}");
 
                     rem -= limit;
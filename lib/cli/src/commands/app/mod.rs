@@ -20,6 +20,7 @@ use crate::commands::AsyncCliCommand;
 
 /// Manage Wasmer Deploy apps.
 #[derive(clap::Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum CmdApp {
     Deploy(deploy::CmdAppDeploy),
     Create(create::CmdAppCreate),
@@ -31,7 +32,7 @@ pub enum CmdApp {
     Delete(delete::CmdAppDelete),
     #[clap(subcommand)]
     Version(version::CmdAppVersion),
-    #[clap(subcommand, alias = "secrets")]
+    #[clap(alias = "secrets")]
     Secret(secrets::CmdAppSecrets),
     #[clap(subcommand, alias = "regions")]
     Region(regions::CmdAppRegions),
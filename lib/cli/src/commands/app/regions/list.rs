@@ -28,7 +28,7 @@ impl AsyncCliCommand for CmdAppRegionsList {
         let client = self.env.client()?;
         let regions = wasmer_backend_api::query::get_all_app_regions(&client).await?;
 
-        println!("{}", self.fmt.format.render(regions.as_slice()));
+        println!("{}", self.fmt.get(&self.env).render(regions.as_slice()));
 
         Ok(())
     }
@@ -0,0 +1,309 @@
+use super::utils::render::{
+    is_structured, render_json_line, render_versioned, OutputVersion, OutputVersionOpts,
+    SecretLogEntry,
+};
+use crate::{
+    commands::{app::util::AppIdentFlag, AsyncCliCommand},
+    config::WasmerEnv,
+    opts::ListFormatOpts,
+    utils::render::ListFormat,
+};
+use futures::{Stream, TryStreamExt};
+use is_terminal::IsTerminal;
+use std::{path::PathBuf, time::Duration};
+use wasmer_backend_api::{
+    secrets::app_secret_log,
+    types::{DeploySecretLogActionChoices, SecretLog},
+    GraphQLApiFailure,
+};
+
+/// Show when the secrets of an app were accessed, as recorded by the server.
+///
+/// Only the names of the secrets and the time of each event are shown, never values. The
+/// server does not record who accessed a secret.
+#[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::TAIL_LOG))]
+pub struct CmdAppSecretsAccessLog {
+    /* --- Common flags --- */
+    #[clap(flatten)]
+    pub env: WasmerEnv,
+
+    /// Don't print any message.
+    #[clap(long)]
+    pub quiet: bool,
+
+    /// Do not prompt for user input.
+    #[clap(long, default_value_t = !std::io::stdin().is_terminal())]
+    pub non_interactive: bool,
+
+    #[clap(flatten)]
+    pub fmt: ListFormatOpts,
+
+    /* --- Flags --- */
+    /// The identifier of the app to show the secret log of.
+    #[clap(flatten)]
+    pub app_id: AppIdentFlag,
+
+    /// The path to the directory where the config file for the application will be written to.
+    #[clap(long = "app-dir", conflicts_with = "app")]
+    pub app_dir_path: Option<PathBuf>,
+
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
+    /// Keep polling for new events, until interrupted.
+    ///
+    /// Events are printed as they arrive: with `--format json`, as one JSON object per line.
+    #[clap(long)]
+    pub follow: bool,
+
+    /// How often to poll for new events with `--follow`, in seconds.
+    #[clap(long, value_name = "SECONDS", default_value_t = 5, requires = "follow", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+
+    /// Only show the events of the given secrets.
+    #[clap(long = "secret", value_name = "NAME")]
+    pub secrets: Vec<String>,
+
+    /// Also show when secrets were created, modified or deleted, not only when they were
+    /// accessed.
+    #[clap(long)]
+    pub all_events: bool,
+
+    #[clap(flatten)]
+    pub output_version: OutputVersionOpts,
+}
+
+impl CmdAppSecretsAccessLog {
+    fn keep(&self, entry: &SecretLog) -> bool {
+        (self.all_events || entry.action == DeploySecretLogActionChoices::Access)
+            && (self.secrets.is_empty() || self.secrets.contains(&entry.secret_name))
+    }
+
+    /// Print the events of each page as it arrives, until interrupted.
+    async fn follow(
+        &self,
+        pages: impl Stream<Item = anyhow::Result<Vec<SecretLog>>>,
+    ) -> anyhow::Result<()> {
+        let format = self.fmt.get(&self.env);
+        let version = self.output_version.version();
+        let mut pages = std::pin::pin!(pages);
+        let mut first = true;
+        while let Some(page) = pages.try_next().await? {
+            let entries: Vec<_> = page
+                .into_iter()
+                .filter(|e| self.keep(e))
+                .map(SecretLogEntry::from)
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            println!("{}", render_page(format, version, &entries, first)?);
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncCliCommand for CmdAppSecretsAccessLog {
+    type Output = ();
+
+    async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
+        let client = self.env.client()?;
+        let app_id = super::utils::get_app_id(
+            &client,
+            self.app_id.app.as_ref(),
+            self.app_dir_path.as_ref(),
+            self.quiet,
+            self.non_interactive,
+            self.guess_from_git,
+        )
+        .await?;
+
+        let pages = app_secret_log(
+            &client,
+            &app_id,
+            self.follow,
+            Duration::from_secs(self.interval),
+        )
+        .map_err(|err| {
+            if is_unsupported(&err) {
+                anyhow::anyhow!(
+                    "The secret log is not supported by the Wasmer API at {}.",
+                    client.graphql_endpoint()
+                )
+            } else {
+                err
+            }
+        });
+
+        if self.follow {
+            return self.follow(pages).await;
+        }
+
+        // Render all the pages at once, so that json and yaml output is a single document.
+        let entries = collect_log(pages, |e| self.keep(e)).await?;
+        if entries.is_empty() {
+            if !self.quiet {
+                eprintln!("No matching events in the secret log.");
+            }
+            return Ok(());
+        }
+        let format = self.fmt.get(&self.env);
+        if is_structured(format) {
+            println!(
+                "{}",
+                render_versioned(format, self.output_version.version(), &entries)?
+            );
+        } else {
+            println!("{}", format.render(&entries));
+        }
+
+        Ok(())
+    }
+}
+
+/// Load the events of all the pages that `keep` selects.
+async fn collect_log(
+    pages: impl Stream<Item = anyhow::Result<Vec<SecretLog>>>,
+    keep: impl Fn(&SecretLog) -> bool,
+) -> anyhow::Result<Vec<SecretLogEntry>> {
+    let pages: Vec<_> = pages.try_collect().await?;
+    Ok(pages
+        .into_iter()
+        .flatten()
+        .filter(|e| keep(e))
+        .map(SecretLogEntry::from)
+        .collect())
+}
+
+/// Render a page of events with `--follow`, so that the pages printed one
+/// after the other still form valid output: one JSON object per line for
+/// json, and a single yaml sequence or table, with the header above the
+/// `first` page only.
+fn render_page(
+    format: ListFormat,
+    version: OutputVersion,
+    entries: &[SecretLogEntry],
+    first: bool,
+) -> anyhow::Result<String> {
+    match format {
+        ListFormat::Json => entries
+            .iter()
+            .map(|e| render_json_line(version, e))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(|lines| lines.join("\n")),
+        ListFormat::Yaml => Ok(render_versioned(format, version, &entries)?
+            .trim_end()
+            .to_string()),
+        ListFormat::Table => Ok(SecretLogEntry::render_rows(entries, first)),
+        ListFormat::ItemTable => Ok(format.render(entries)),
+    }
+}
+
+/// Whether `err` tells that the API does not know about the secret log.
+fn is_unsupported(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<GraphQLApiFailure>()
+        .is_some_and(|failure| {
+            failure.errors.iter().any(|e| {
+                e.message.contains("Cannot query field") && e.message.contains("getAppSecretLog")
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmer_backend_api::mock::MockSecretsApi;
+
+    fn mock_log() -> MockSecretsApi {
+        use DeploySecretLogActionChoices::*;
+        [
+            ("A", Create),
+            ("A", Access),
+            ("B", Access),
+            ("A", Modification),
+            ("B", Access),
+        ]
+        .into_iter()
+        .fold(MockSecretsApi::new(), |api, (name, action)| {
+            api.with_log_event("da_1", name, action)
+        })
+        .with_max_log_page_size(2)
+    }
+
+    #[tokio::test]
+    async fn test_collect_log_reads_all_pages() {
+        let api = mock_log();
+        let pages = app_secret_log(&api, "da_1", false, Duration::ZERO);
+        let entries = collect_log(pages, |e| e.action == DeploySecretLogActionChoices::Access)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| e.secret.as_str())
+                .collect::<Vec<_>>(),
+            ["A", "B", "B"]
+        );
+        assert_eq!(
+            api.calls(),
+            [
+                "get_app_secret_log_page(da_1, -)",
+                "get_app_secret_log_page(da_1, 1)",
+                "get_app_secret_log_page(da_1, 3)",
+                "get_app_secret_log_page(da_1, 4)",
+            ]
+        );
+
+        // Rendered once, the pages form a single JSON document.
+        let json = render_versioned(ListFormat::Json, OutputVersion::V1, &entries).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_follow_pages_render_as_ndjson() {
+        let api = mock_log();
+        let pages: Vec<_> = app_secret_log(&api, "da_1", false, Duration::ZERO)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(pages.len(), 3);
+
+        let mut out = String::new();
+        for (i, page) in pages.into_iter().enumerate() {
+            let entries: Vec<_> = page.into_iter().map(SecretLogEntry::from).collect();
+            let page = render_page(ListFormat::Json, OutputVersion::V1, &entries, i == 0);
+            out.push_str(&page.unwrap());
+            out.push('\n');
+        }
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[3]["secret"], "A");
+        assert_eq!(lines[3]["action"], "modification");
+    }
+
+    fn failure(message: &str) -> anyhow::Error {
+        let errors = serde_json::from_value(serde_json::json!([{ "message": message }])).unwrap();
+        anyhow::Error::new(GraphQLApiFailure { errors }).context("GraphQL query failed")
+    }
+
+    #[test]
+    fn test_is_unsupported() {
+        assert!(is_unsupported(&failure(
+            "Cannot query field \"getAppSecretLog\" on type \"Query\"."
+        )));
+        assert!(!is_unsupported(&failure("App not found")));
+        assert!(!is_unsupported(&anyhow::anyhow!(
+            "Cannot query field \"getAppSecretLog\""
+        )));
+    }
+}
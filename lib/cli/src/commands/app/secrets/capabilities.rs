@@ -0,0 +1,97 @@
+//! A machine-readable description of the secret commands, printed by
+//! `wasmer app secret --capabilities`.
+//!
+//! The document is generated from the clap command tree, so it always matches
+//! what the build actually supports.
+
+use crate::config::WasmerEnv;
+use clap::CommandFactory;
+use std::collections::HashSet;
+
+/// Bumped whenever the structure of the document changes in an incompatible way.
+const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, serde::Serialize)]
+pub(super) struct Capabilities {
+    schema_version: u32,
+    cli_version: &'static str,
+    subcommands: Vec<Subcommand>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Subcommand {
+    name: String,
+    aliases: Vec<String>,
+    about: Option<String>,
+    /// The flags of the subcommand, without the ones shared by all commands
+    /// (`--registry`, `--token`, ...).
+    flags: Vec<Flag>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct Flag {
+    name: String,
+    takes_value: bool,
+}
+
+impl Capabilities {
+    /// Describe the subcommands of the given command.
+    pub fn from_command(cmd: &clap::Command) -> Self {
+        let common: HashSet<String> = WasmerEnv::command()
+            .get_arguments()
+            .filter_map(|a| a.get_long().map(str::to_string))
+            .collect();
+
+        let subcommands = cmd
+            .get_subcommands()
+            .filter(|c| !c.is_hide_set())
+            .map(|c| Subcommand {
+                name: c.get_name().to_string(),
+                aliases: c.get_all_aliases().map(str::to_string).collect(),
+                about: c.get_about().map(|a| a.to_string()),
+                flags: c
+                    .get_arguments()
+                    .filter(|a| !a.is_hide_set())
+                    .filter_map(|a| {
+                        let name = a.get_long()?;
+                        (!common.contains(name)).then(|| Flag {
+                            name: name.to_string(),
+                            takes_value: a.get_action().takes_values(),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            cli_version: env!("CARGO_PKG_VERSION"),
+            subcommands,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_from_command_tree() {
+        let caps = Capabilities::from_command(&super::super::CmdAppSecrets::command());
+        assert_eq!(caps.schema_version, SCHEMA_VERSION);
+
+        let reveal = caps
+            .subcommands
+            .iter()
+            .find(|c| c.name == "reveal")
+            .unwrap();
+        let all = reveal.flags.iter().find(|f| f.name == "all").unwrap();
+        assert!(!all.takes_value);
+        let version = reveal.flags.iter().find(|f| f.name == "version").unwrap();
+        assert!(version.takes_value);
+        // Flags shared by all commands are not repeated for every subcommand.
+        assert!(!reveal.flags.iter().any(|f| f.name == "registry"));
+
+        assert!(caps.subcommands.iter().any(|c| c.name == "export"));
+    }
+}
@@ -0,0 +1,161 @@
+use super::utils;
+use crate::{
+    commands::{app::util::AppIdent, AsyncCliCommand},
+    config::WasmerEnv,
+};
+use anyhow::Context;
+use colored::Colorize;
+use std::str::FromStr;
+
+/// Copy a secret from one app to another.
+#[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::COPY))]
+pub struct CmdAppSecretsCopy {
+    /* --- Common flags --- */
+    #[clap(flatten)]
+    pub env: WasmerEnv,
+
+    /// Don't print any message.
+    #[clap(long)]
+    pub quiet: bool,
+
+    /* --- Flags --- */
+    /// Replace the secret if it already exists in the destination app.
+    #[clap(long)]
+    pub overwrite: bool,
+
+    /* --- Parameters --- */
+    /// The secret to copy, as `<app>:<name>`.
+    #[clap(value_name = "SRC_APP:NAME")]
+    pub source: SecretRef,
+
+    /// The app to copy the secret to, as `<app>` or `<app>:<new-name>`.
+    #[clap(value_name = "DST_APP[:NEW_NAME]")]
+    pub destination: SecretRef,
+}
+
+/// A reference to a secret of an app, as `<app>[:<name>]`.
+#[derive(Debug, Clone)]
+pub struct SecretRef {
+    pub app: AppIdent,
+    pub name: Option<String>,
+}
+
+impl FromStr for SecretRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (app, name) = match s.rsplit_once(':') {
+            // The scheme of an app URL, e.g. `https://my-app.wasmer.app`.
+            Some((_, rest)) if rest.starts_with("//") => (s, None),
+            Some((app, name)) => {
+                if name.is_empty() {
+                    anyhow::bail!("invalid secret reference '{s}': the secret name is empty");
+                }
+                (app, Some(name.to_string()))
+            }
+            None => (s, None),
+        };
+        if app.is_empty() {
+            anyhow::bail!("invalid secret reference '{s}': the app is empty");
+        }
+
+        Ok(Self {
+            app: AppIdent::from_str(app)?,
+            name,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncCliCommand for CmdAppSecretsCopy {
+    type Output = ();
+
+    async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
+        let source_name = self.source.name.as_deref().context(
+            "No secret name given for the source. Use `<app>:<name>` to select the secret to copy.",
+        )?;
+        let target_name = self.destination.name.as_deref().unwrap_or(source_name);
+        utils::validation::validate_secret_name(target_name)?;
+
+        let client = utils::mutation_client(&self.env)?;
+
+        let source_app = self.source.app.resolve(&client).await?;
+        let target_app = self.destination.app.resolve(&client).await?;
+        let source_app_id = source_app.id.inner();
+        let target_app_id = target_app.id.inner();
+
+        if source_app_id == target_app_id && source_name == target_name {
+            anyhow::bail!("The source and the destination are the same secret.");
+        }
+
+        let value = utils::get_secret_value_by_name(&client, source_app_id, source_name).await?;
+
+        if !self.overwrite
+            && utils::get_secret_by_name(&client, target_app_id, target_name)
+                .await?
+                .is_some()
+        {
+            anyhow::bail!(
+                "Secret '{}' already exists in app {}. Use `--overwrite` to replace it.",
+                target_name.bold(),
+                target_app.name.bold()
+            );
+        }
+
+        let res = wasmer_backend_api::query::upsert_app_secrets(
+            &client,
+            target_app_id,
+            [(target_name, value.as_str())],
+        )
+        .await?
+        .context("Backend did not return any payload to confirm the copy of the secret!")?;
+        if !res.success {
+            anyhow::bail!("Copying the secret failed!");
+        }
+
+        if !self.quiet {
+            eprintln!(
+                "Copied secret '{}' of app {} to '{}' in app {}.",
+                source_name.bold(),
+                source_app.name.bold(),
+                target_name.bold(),
+                target_app.name.bold()
+            );
+            eprintln!(
+                "{}: In order for secrets to appear in your app, re-deploy it.",
+                "Info".bold()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secret_ref() {
+        let r = SecretRef::from_str("my-org/staging:DB_URL").unwrap();
+        assert_eq!(
+            r.app,
+            AppIdent::NamespacedName("my-org".to_string(), "staging".to_string())
+        );
+        assert_eq!(r.name.as_deref(), Some("DB_URL"));
+
+        let r = SecretRef::from_str("prod").unwrap();
+        assert_eq!(r.app, AppIdent::Name("prod".to_string()));
+        assert_eq!(r.name, None);
+
+        let r = SecretRef::from_str("https://my-app.wasmer.app:DB_URL").unwrap();
+        assert_eq!(r.app, AppIdent::Url("my-app.wasmer.app".to_string()));
+        assert_eq!(r.name.as_deref(), Some("DB_URL"));
+        let r = SecretRef::from_str("https://my-app.wasmer.app").unwrap();
+        assert_eq!(r.name, None);
+
+        assert!(SecretRef::from_str("prod:").is_err());
+        assert!(SecretRef::from_str(":DB_URL").is_err());
+    }
+}
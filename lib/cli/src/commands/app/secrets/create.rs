@@ -1,7 +1,8 @@
-use super::utils::Secret;
+use super::utils::{batch::BatchResults, rename::RenameOpts, Secret};
 use crate::{
     commands::{app::util::AppIdentFlag, AsyncCliCommand},
     config::WasmerEnv,
+    opts::ListFormatOpts,
 };
 use anyhow::Context;
 use colored::Colorize;
@@ -15,6 +16,7 @@ use wasmer_backend_api::WasmerClient;
 
 /// Create a new app secret.
 #[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::CREATE))]
 pub struct CmdAppSecretsCreate {
     /* --- Common flags --- */
     #[clap(flatten)]
@@ -28,11 +30,21 @@ pub struct CmdAppSecretsCreate {
     #[clap(long, default_value_t = !std::io::stdin().is_terminal())]
     pub non_interactive: bool,
 
+    /// The format of the report printed when the input is invalid, and of the per-secret report
+    /// printed when creating multiple secrets.
+    #[clap(flatten)]
+    pub fmt: Option<ListFormatOpts>,
+
     /* --- Flags --- */
     /// The path to the directory where the config file for the application will be written to.
     #[clap(long = "app-dir", conflicts_with = "app")]
     pub app_dir_path: Option<PathBuf>,
 
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
     #[clap(flatten)]
     pub app_id: AppIdentFlag,
 
@@ -40,10 +52,51 @@ pub struct CmdAppSecretsCreate {
     #[clap(long, name = "from-file", conflicts_with = "name")]
     pub from_file: Option<PathBuf>,
 
+    /// Read the secrets to create from stdin, as a flat JSON object (`{"NAME": "value", ...}`).
+    #[clap(
+        long,
+        name = "from-json-stdin",
+        conflicts_with = "name",
+        conflicts_with = "from-file"
+    )]
+    pub from_json_stdin: bool,
+
+    /// Read the value of the secret from stdin.
+    ///
+    /// All bytes are kept as they are, except for a single trailing newline (`\n` or `\r\n`),
+    /// which is stripped unless `--no-trim` is passed.
+    #[clap(
+        long,
+        conflicts_with_all = ["value", "from-file", "from-json-stdin"]
+    )]
+    pub value_stdin: bool,
+
+    /// Strip a single trailing newline from the value read with `--value-stdin` (the default).
+    #[clap(long, overrides_with = "no_trim", requires = "value_stdin")]
+    pub trim: bool,
+
+    /// Keep the value read with `--value-stdin` exactly as it is, including a trailing newline.
+    #[clap(long, overrides_with = "trim", requires = "value_stdin")]
+    pub no_trim: bool,
+
+    /// Allow secrets with an empty value.
+    ///
+    /// Empty values are rejected by default, as they are usually a mistake.
+    #[clap(long)]
+    pub allow_empty: bool,
+
+    #[clap(flatten)]
+    pub rename: RenameOpts,
+
     /// Whether or not to redeploy the app after creating the secrets.
     #[clap(long)]
     pub redeploy: bool,
 
+    /// When creating multiple secrets, abort on the first error instead of reporting the outcome
+    /// of each creation.
+    #[clap(long)]
+    pub fail_fast: bool,
+
     /* --- Parameters --- */
     /// The name of the secret to create.
     #[clap(name = "name")]
@@ -75,7 +128,9 @@ impl CmdAppSecretsCreate {
             return Ok(value.clone());
         }
 
-        if self.non_interactive {
+        if self.value_stdin {
+            super::utils::read_value(std::io::stdin().lock(), !self.no_trim)
+        } else if self.non_interactive {
             anyhow::bail!("No secret value given. Provide one as a positional argument.")
         } else {
             let theme = ColorfulTheme::default();
@@ -87,22 +142,33 @@ impl CmdAppSecretsCreate {
 
     /// Given a list of secrets, checks if the given secrets already exist for the given app and
     /// returns a list of secrets that must be upserted.
+    ///
+    /// Secrets that cannot be created are returned as errors, so that they are reported along
+    /// with the others.
     async fn filter_secrets(
         &self,
         client: &WasmerClient,
         app_id: &str,
         secrets: Vec<Secret>,
-    ) -> anyhow::Result<Vec<Secret>> {
+    ) -> anyhow::Result<Vec<Result<Secret, (String, anyhow::Error)>>> {
+        super::utils::validation::validate_secret_values(&secrets, self.allow_empty)?;
+
         let names = secrets.iter().map(|s| &s.name);
         let app_secrets =
             wasmer_backend_api::query::get_all_app_secrets_filtered(client, app_id, names).await?;
         let mut sset = HashSet::<String>::from_iter(app_secrets.iter().map(|s| s.name.clone()));
         let mut ret = HashMap::new();
+        let mut rejected = Vec::new();
 
         for secret in secrets {
             if sset.contains(&secret.name) {
                 if self.non_interactive {
-                    anyhow::bail!("Cannot create secret '{}' as it already exists. Use the `update` command instead.", secret.name.bold());
+                    let err = anyhow::anyhow!("Cannot create secret '{}' as it already exists. Use the `update` command instead.", secret.name.bold());
+                    if self.fail_fast {
+                        return Err(err);
+                    }
+                    rejected.push((secret.name, err));
+                    continue;
                 } else {
                     if ret.contains_key(&secret.name) {
                         eprintln!(
@@ -132,7 +198,8 @@ impl CmdAppSecretsCreate {
 
         Ok(ret
             .into_iter()
-            .map(|(name, value)| Secret { name, value })
+            .map(|(name, value)| Ok(Secret { name, value }))
+            .chain(rejected.into_iter().map(Err))
             .collect())
     }
 
@@ -154,37 +221,83 @@ impl CmdAppSecretsCreate {
 
         if !res.success {
             anyhow::bail!("Secret creation failed!")
-        } else {
-            if !self.quiet {
-                eprintln!("Succesfully created secret(s):");
-                for secret in &secrets {
-                    eprintln!("{}", secret.name.bold());
+        }
+        self.created(client, app_id, secrets.iter().map(|s| s.name.as_str()))
+            .await
+    }
+
+    /// Create the secrets one by one, and report the outcome of each.
+    async fn create_many(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        secrets: Vec<Result<Secret, (String, anyhow::Error)>>,
+    ) -> anyhow::Result<()> {
+        let mut results = BatchResults::default();
+        let mut created = Vec::new();
+
+        for secret in secrets {
+            let (name, res) = match secret {
+                Ok(secret) => {
+                    let res = create_one(client, app_id, &secret).await;
+                    (secret.name, res)
+                }
+                Err((name, err)) => (name, Err(err)),
+            };
+            if self.fail_fast {
+                res?;
+                created.push(name);
+            } else {
+                if res.is_ok() {
+                    created.push(name.clone());
                 }
+                results.push(name, res);
+            }
+        }
 
-                let should_redeploy = self.redeploy || {
-                    if !self.non_interactive && self.from_file.is_some() {
-                        let theme = ColorfulTheme::default();
-                        dialoguer::Confirm::with_theme(&theme)
-                            .with_prompt("Do you want to redeploy your app?")
-                            .interact()?
-                    } else {
-                        false
-                    }
-                };
+        if !created.is_empty() {
+            self.created(client, app_id, created.iter().map(String::as_str))
+                .await?;
+        }
+        results.finish(self.fmt.as_ref().map(|f| f.get(&self.env)), "create")
+    }
+
+    /// Report the created secrets, and redeploy the app if requested.
+    async fn created(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        names: impl Iterator<Item = &str>,
+    ) -> anyhow::Result<()> {
+        if !self.quiet {
+            eprintln!("Succesfully created secret(s):");
+            for name in names {
+                eprintln!("{}", name.bold());
+            }
 
-                if should_redeploy {
-                    wasmer_backend_api::query::redeploy_app_by_id(client, app_id).await?;
-                    eprintln!("{} Deployment complete", "𖥔".yellow().bold());
+            let should_redeploy = self.redeploy || {
+                if !self.non_interactive && (self.from_file.is_some() || self.from_json_stdin) {
+                    let theme = ColorfulTheme::default();
+                    dialoguer::Confirm::with_theme(&theme)
+                        .with_prompt("Do you want to redeploy your app?")
+                        .interact()?
                 } else {
-                    eprintln!(
-                        "{}: In order for secrets to appear in your app, re-deploy it.",
-                        "Info".bold()
-                    );
+                    false
                 }
-            }
+            };
 
-            Ok(())
+            if should_redeploy {
+                wasmer_backend_api::query::redeploy_app_by_id(client, app_id).await?;
+                eprintln!("{} Deployment complete", "𖥔".yellow().bold());
+            } else {
+                eprintln!(
+                    "{}: In order for secrets to appear in your app, re-deploy it.",
+                    "Info".bold()
+                );
+            }
         }
+
+        Ok(())
     }
 
     async fn create_from_file(
@@ -194,35 +307,81 @@ impl CmdAppSecretsCreate {
         app_id: &str,
     ) -> anyhow::Result<(), anyhow::Error> {
         let secrets = super::utils::read_secrets_from_file(path).await?;
+        let secrets = self.rename.apply(secrets, self.quiet)?;
 
         let secrets = self.filter_secrets(client, app_id, secrets).await?;
-        self.create(client, app_id, secrets).await?;
+        self.create_many(client, app_id, secrets).await
+    }
 
-        Ok(())
+    async fn create_from_json(
+        &self,
+        client: &WasmerClient,
+        secrets: Vec<Secret>,
+        app_id: &str,
+    ) -> anyhow::Result<(), anyhow::Error> {
+        let secrets = self.filter_secrets(client, app_id, secrets).await?;
+        self.create_many(client, app_id, secrets).await
     }
 }
 
+async fn create_one(client: &WasmerClient, app_id: &str, secret: &Secret) -> anyhow::Result<()> {
+    let res =
+        wasmer_backend_api::query::upsert_app_secret(client, app_id, &secret.name, &secret.value)
+            .await?
+            .context("Backend did not return any payload to confirm the creation of the secret")?;
+    if !res.success {
+        anyhow::bail!("the backend did not create the secret");
+    }
+    Ok(())
+}
+
 #[async_trait::async_trait]
 impl AsyncCliCommand for CmdAppSecretsCreate {
     type Output = ();
 
     async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
-        let client = self.env.client()?;
+        let format = self.fmt.as_ref().map(|f| f.get(&self.env));
+        self.run()
+            .await
+            .map_err(|e| super::utils::validation::report(e, format))
+    }
+}
+
+impl CmdAppSecretsCreate {
+    async fn run(self) -> anyhow::Result<()> {
+        if !self.rename.rules.is_empty() && self.from_file.is_none() && !self.from_json_stdin {
+            anyhow::bail!("`--rename` can only be used with `--from-file` or `--from-json-stdin`");
+        }
+
+        // Parse the input before talking to the backend, so that malformed JSON never results in
+        // a partial operation.
+        let json_secrets = if self.from_json_stdin {
+            let secrets = super::utils::read_secrets_from_json(std::io::stdin().lock())?;
+            Some(self.rename.apply(secrets, self.quiet)?)
+        } else {
+            None
+        };
+
+        let client = super::utils::mutation_client(&self.env)?;
         let app_id = super::utils::get_app_id(
             &client,
             self.app_id.app.as_ref(),
             self.app_dir_path.as_ref(),
             self.quiet,
             self.non_interactive,
+            self.guess_from_git,
         )
         .await?;
         if let Some(file) = &self.from_file {
             self.create_from_file(&client, file, &app_id).await
+        } else if let Some(secrets) = json_secrets {
+            self.create_from_json(&client, secrets, &app_id).await
         } else {
             let name = self.get_secret_name()?;
             let value = self.get_secret_value()?;
-            let secret = Secret { name, value };
-            self.create(&client, &app_id, vec![secret]).await
+            let secrets = vec![Secret { name, value }];
+            super::utils::validation::validate_secret_values(&secrets, self.allow_empty)?;
+            self.create(&client, &app_id, secrets).await
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::{
     commands::{app::util::AppIdentFlag, AsyncCliCommand},
     config::WasmerEnv,
+    opts::ListFormatOpts,
 };
 use colored::Colorize;
 use dialoguer::theme::ColorfulTheme;
@@ -8,10 +9,11 @@ use is_terminal::IsTerminal;
 use std::path::{Path, PathBuf};
 use wasmer_backend_api::WasmerClient;
 
-use super::utils::{self, get_secrets};
+use super::utils::{self, batch::BatchResults, get_secrets};
 
 /// Delete an existing app secret.
 #[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::DELETE))]
 pub struct CmdAppSecretsDelete {
     /* --- Common flags --- */
     #[clap(flatten)]
@@ -25,6 +27,10 @@ pub struct CmdAppSecretsDelete {
     #[clap(long, default_value_t = !std::io::stdin().is_terminal())]
     pub non_interactive: bool,
 
+    /// The format of the per-secret report printed when deleting multiple secrets.
+    #[clap(flatten)]
+    pub fmt: Option<ListFormatOpts>,
+
     /* --- Flags --- */
     #[clap(flatten)]
     pub app_id: AppIdentFlag,
@@ -33,6 +39,11 @@ pub struct CmdAppSecretsDelete {
     #[clap(long = "app-dir", conflicts_with = "app")]
     pub app_dir_path: Option<PathBuf>,
 
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
     /// Path to a file with secrets stored in JSON format to delete secrets from.
     #[clap(
         long,
@@ -50,6 +61,11 @@ pub struct CmdAppSecretsDelete {
     #[clap(long)]
     pub force: bool,
 
+    /// When deleting multiple secrets, abort on the first error instead of reporting the outcome
+    /// of each deletion.
+    #[clap(long)]
+    pub fail_fast: bool,
+
     /* --- Parameters --- */
     /// The name of the secret to delete.
     #[clap(name = "name")]
@@ -123,12 +139,28 @@ impl CmdAppSecretsDelete {
         app_id: String,
     ) -> anyhow::Result<()> {
         let secrets = super::utils::read_secrets_from_file(path).await?;
+        self.delete_many(client, &app_id, secrets.into_iter().map(|s| s.name))
+            .await
+    }
 
-        for secret in secrets {
-            self.delete(client, &app_id, &secret.name).await?;
+    async fn delete_many(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        names: impl Iterator<Item = String>,
+    ) -> anyhow::Result<()> {
+        let mut results = BatchResults::default();
+
+        for name in names {
+            let res = self.delete(client, app_id, &name).await;
+            if self.fail_fast {
+                res?;
+            } else {
+                results.push(name, res);
+            }
         }
 
-        Ok(())
+        results.finish(self.fmt.as_ref().map(|f| f.get(&self.env)), "delete")
     }
 }
 
@@ -137,13 +169,14 @@ impl AsyncCliCommand for CmdAppSecretsDelete {
     type Output = ();
 
     async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
-        let client = self.env.client()?;
+        let client = utils::mutation_client(&self.env)?;
         let app_id = super::utils::get_app_id(
             &client,
             self.app_id.app.as_ref(),
             self.app_dir_path.as_ref(),
             self.quiet,
             self.non_interactive,
+            self.guess_from_git,
         )
         .await?;
         if let Some(file) = &self.from_file {
@@ -153,11 +186,8 @@ impl AsyncCliCommand for CmdAppSecretsDelete {
                 anyhow::bail!("Refusing to delete all secrets in non-interactive mode without the `--force` flag.")
             }
             let secrets = get_secrets(&client, &app_id).await?;
-            for secret in secrets {
-                self.delete(&client, &app_id, &secret.name).await?;
-            }
-
-            Ok(())
+            self.delete_many(&client, &app_id, secrets.into_iter().map(|s| s.name))
+                .await
         } else {
             let name = self.get_secret_name()?;
             self.delete(&client, &app_id, &name).await
@@ -0,0 +1,206 @@
+//! Usage examples shown in the `--help` output of the secret commands.
+
+/// A single usage example.
+pub(super) struct Example {
+    pub description: &'static str,
+    pub command: &'static str,
+}
+
+pub(super) const COPY: &[Example] = &[
+    Example {
+        description: "Promote a secret from staging to production",
+        command: "wasmer app secret cp my-org/staging:DB_URL my-org/prod",
+    },
+    Example {
+        description: "Copy a secret under a new name, replacing it if it exists",
+        command: "wasmer app secret cp my-org/staging:DB_URL my-org/prod:LEGACY_DB_URL --overwrite",
+    },
+];
+
+pub(super) const CREATE: &[Example] = &[
+    Example {
+        description: "Create a single secret",
+        command: "wasmer app secret create --app my-org/my-app DB_URL postgres://...",
+    },
+    Example {
+        description: "Create a secret from the contents of a file, without the trailing newline",
+        command: "wasmer app secret create --app my-org/my-app TLS_KEY --value-stdin < key.pem",
+    },
+    Example {
+        description: "Create secrets from a dotenv file",
+        command: "wasmer app secret create --app my-org/my-app --from-file .env",
+    },
+    Example {
+        description: "Create secrets from a JSON object, e.g. in CI",
+        command: "echo \"$SECRETS_JSON\" | wasmer app secret create --app my-org/my-app --from-json-stdin",
+    },
+    Example {
+        description: "Import staging secrets into another app, dropping their prefix",
+        command: "wasmer app secret create --app my-org/my-app --from-file staging.env --rename 's/^STAGING_//'",
+    },
+];
+
+pub(super) const DELETE: &[Example] = &[
+    Example {
+        description: "Delete a single secret",
+        command: "wasmer app secret delete --app my-org/my-app DB_URL",
+    },
+    Example {
+        description: "Delete all secrets without confirmation, reporting each outcome as JSON",
+        command: "wasmer app secret delete --app my-org/my-app --all --force --format json",
+    },
+];
+
+pub(super) const EXISTS: &[Example] = &[
+    Example {
+        description: "Create a secret only if it does not exist yet",
+        command: "wasmer app secret exists --app my-org/my-app DB_URL || wasmer app secret create --app my-org/my-app DB_URL \"$DB_URL\"",
+    },
+    Example {
+        description: "Tell whether a secret exists",
+        command: "wasmer app secret exists --app my-org/my-app DB_URL --print",
+    },
+];
+
+pub(super) const EXPORT: &[Example] = &[
+    Example {
+        description: "Export the secrets of an app to a dotenv file",
+        command: "wasmer app secret export --app my-org/my-app > .env",
+    },
+    Example {
+        description: "Export the secrets of the app in the given directory as JSON",
+        command: "wasmer app secret export --app-dir ./my-app --format json | jq .",
+    },
+    Example {
+        description: "Export only the secrets that changed since the last export",
+        command: "wasmer app secret export --app my-org/my-app --only-changed last.env > delta.env",
+    },
+    Example {
+        description:
+            "Generate a .env.example template with the names of the secrets, but no values",
+        command: "wasmer app secret export --app my-org/my-app --example > .env.example",
+    },
+    Example {
+        description: "Decode base64-encoded values while exporting them",
+        command: "wasmer app secret export --app my-org/my-app --value-filter 'base64 -d' --i-understand-value-filter > .env",
+    },
+];
+
+pub(super) const LIST: &[Example] = &[
+    Example {
+        description: "List the secrets of the app in the current directory",
+        command: "wasmer app secret list",
+    },
+    Example {
+        description: "List the secrets of an app as JSON",
+        command: "wasmer app secret list --app my-org/my-app --format json",
+    },
+    Example {
+        description: "List the secrets changed in the last 7 days",
+        command: "wasmer app secret list --app my-org/my-app --since 7d",
+    },
+];
+
+pub(super) const TAIL_LOG: &[Example] = &[
+    Example {
+        description: "Show when the secrets of an app were accessed",
+        command: "wasmer app secret tail-log --app my-org/my-app",
+    },
+    Example {
+        description: "Follow the accesses to a single secret as they happen",
+        command: "wasmer app secret tail-log --app my-org/my-app --secret DB_URL --follow",
+    },
+    Example {
+        description: "Export the full secret log, including changes, as JSON",
+        command: "wasmer app secret tail-log --app my-org/my-app --all-events --format json",
+    },
+];
+
+pub(super) const REVEAL: &[Example] = &[
+    Example {
+        description: "Reveal a single secret",
+        command: "wasmer app secret reveal --app my-org/my-app DB_URL --format table",
+    },
+    Example {
+        description: "Get the raw value of a secret, for scripting",
+        command: "DB_URL=$(wasmer app secret reveal --app my-org/my-app DB_URL)",
+    },
+    Example {
+        description: "Reveal all secrets in dotenv format",
+        command: "wasmer app secret reveal --app my-org/my-app --all > .env",
+    },
+    Example {
+        description: "Reveal all secrets as JSON",
+        command: "wasmer app secret reveal --app my-org/my-app --all --format json",
+    },
+    Example {
+        description: "Reveal all secrets as JSON, together with the app and the time of the fetch",
+        command: "wasmer app secret reveal --app my-org/my-app --all --format json --envelope",
+    },
+    Example {
+        description:
+            "Hand all secrets to another process through a pipe, without touching the disk",
+        command: "wasmer app secret reveal --app my-org/my-app --all --out-fd 3 3> >(consumer)",
+    },
+    Example {
+        description: "Archive all secrets as JSON in a gzip-compressed file",
+        command:
+            "wasmer app secret reveal --app my-org/my-app --all --format json --out secrets.json.gz",
+    },
+    Example {
+        description: "Write all secrets to a file with a detached signature of its contents",
+        command: "wasmer app secret reveal --app my-org/my-app --all --canonical --out secrets.env --sign key.pem",
+    },
+    Example {
+        description: "Check that a signed file was not tampered with",
+        command: "wasmer app secret reveal --verify secrets.env --public-key key.pub",
+    },
+    Example {
+        description: "Stop a deployment unless a secret exists and has the expected value",
+        command: "wasmer app secret reveal --app my-org/my-app --assert-name DB_URL --assert-hash \"$(printf %s \"$DB_URL\" | sha256sum | cut -d' ' -f1)\"",
+    },
+    Example {
+        description: "List the secrets set on an app, without fetching any value",
+        command: "wasmer app secret reveal --app my-org/my-app --presence",
+    },
+    Example {
+        description: "Follow a credential rotation, checking the secret every 10 seconds",
+        command:
+            "wasmer app secret reveal --app my-org/my-app API_KEY --watch --interval 10s --mask",
+    },
+];
+
+pub(super) const ROTATE: &[Example] = &[
+    Example {
+        description: "Replace the value of a single secret with a new random one",
+        command: "wasmer app secret rotate --app my-org/my-app SESSION_KEY",
+    },
+    Example {
+        description: "Rotate all secrets except the database ones, without prompting",
+        command: "wasmer app secret rotate --app my-org/my-app --all --exclude 'DB_*' --yes",
+    },
+];
+
+pub(super) const UPDATE: &[Example] = &[
+    Example {
+        description: "Update a single secret",
+        command: "wasmer app secret update --app my-org/my-app DB_URL postgres://...",
+    },
+    Example {
+        description: "Update secrets from a dotenv file and redeploy the app",
+        command: "wasmer app secret update --app my-org/my-app --from-file .env --redeploy",
+    },
+];
+
+/// Render examples as an `after_help` section.
+pub(super) fn render(examples: &[Example]) -> String {
+    let mut out = String::from("Examples:");
+    for Example {
+        description,
+        command,
+    } in examples
+    {
+        out.push_str(&format!("\n  # {description}\n  $ {command}\n"));
+    }
+    out
+}
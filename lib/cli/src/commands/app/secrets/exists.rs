@@ -0,0 +1,87 @@
+use super::utils;
+use crate::{
+    commands::{app::util::AppIdentFlag, AsyncCliCommand},
+    config::WasmerEnv,
+    error::ExitCodeError,
+};
+use is_terminal::IsTerminal;
+use std::path::PathBuf;
+
+/// The exit code used when the secret does not exist.
+pub(crate) const EXIT_CODE_NOT_FOUND: i32 = 3;
+
+/// Check whether an app secret exists, without revealing its value.
+///
+/// Exits with 0 if the secret exists, and with 3 if it does not. Any other failure (e.g. the app
+/// could not be found) exits with 1.
+#[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::EXISTS))]
+pub struct CmdAppSecretsExists {
+    /* --- Common flags --- */
+    #[clap(flatten)]
+    pub env: WasmerEnv,
+
+    /// Print whether the secret exists. Nothing is printed by default.
+    #[clap(long)]
+    pub print: bool,
+
+    /// Do not prompt for user input.
+    #[clap(long, default_value_t = !std::io::stdin().is_terminal())]
+    pub non_interactive: bool,
+
+    /* --- Flags --- */
+    #[clap(flatten)]
+    pub app_id: AppIdentFlag,
+
+    /// The path to the directory where the config file for the application will be written to.
+    #[clap(long = "app-dir", conflicts_with = "app")]
+    pub app_dir_path: Option<PathBuf>,
+
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
+    /* --- Parameters --- */
+    /// The name of the secret to look for.
+    #[clap(name = "name")]
+    pub secret_name: String,
+}
+
+#[async_trait::async_trait]
+impl AsyncCliCommand for CmdAppSecretsExists {
+    type Output = ();
+
+    async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
+        let client = self.env.client()?;
+        let resolution = utils::resolve_app(
+            &client,
+            self.app_id.app.as_ref(),
+            self.app_dir_path.as_ref(),
+            !self.print,
+            self.non_interactive,
+            self.guess_from_git,
+        )
+        .await?;
+
+        // Only the metadata of the secret is fetched, never its value.
+        let exists = utils::get_secret_by_name(&client, &resolution.app_id, &self.secret_name)
+            .await?
+            .is_some();
+
+        if self.print {
+            let verb = if exists { "exists" } else { "does not exist" };
+            println!(
+                "Secret '{}' {verb} in app {}.",
+                self.secret_name,
+                resolution.app_label()
+            );
+        }
+
+        if exists {
+            Ok(())
+        } else {
+            Err(ExitCodeError::new(EXIT_CODE_NOT_FOUND, "").into())
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use super::utils;
+use crate::{
+    commands::{app::util::AppIdentFlag, AsyncCliCommand},
+    config::WasmerEnv,
+    error::ExitCodeError,
+    utils::render::ListFormat,
+};
+use is_terminal::IsTerminal;
+use std::path::PathBuf;
+
+/// Export all the secrets of an app, for use in scripts and pipelines.
+///
+/// Unlike `reveal`, this command never prompts: the app must be given explicitly with `--app` or
+/// `--app-dir`, and the secrets are not written to a terminal unless `--force` is passed.
+#[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::EXPORT))]
+#[clap(group(clap::ArgGroup::new("target").required(true).args(["app", "app_dir_path"])))]
+pub struct CmdAppSecretsExport {
+    /* --- Common flags --- */
+    #[clap(flatten)]
+    pub env: WasmerEnv,
+
+    /// Output format.
+    #[clap(short = 'f', long, value_enum, default_value_t = ExportFormat::Dotenv)]
+    pub format: ExportFormat,
+
+    /* --- Flags --- */
+    #[clap(flatten)]
+    pub app_id: AppIdentFlag,
+
+    /// The path to the directory containing the config file of the application.
+    #[clap(long = "app-dir", conflicts_with = "app")]
+    pub app_dir_path: Option<PathBuf>,
+
+    /// Only export the secrets that are missing from, or have a different value than in, the
+    /// given baseline file (dotenv, or a flat JSON object if the file ends in `.json`).
+    #[clap(long, value_name = "BASELINE")]
+    pub only_changed: Option<PathBuf>,
+
+    /// Print a `.env.example` template instead: one `NAME=` line per secret, without values.
+    ///
+    /// Only the names of the secrets are fetched, so this is safe to print anywhere.
+    #[clap(long, conflicts_with_all = ["format", "only_changed"])]
+    pub example: bool,
+
+    #[clap(flatten)]
+    pub output_version: utils::render::OutputVersionOpts,
+
+    #[clap(flatten)]
+    pub value_filter: utils::value_filter::ValueFilterOpts,
+
+    /// Write the secrets to stdout even if it is a terminal.
+    #[clap(long)]
+    pub force: bool,
+
+    /// How many secret values to fetch at the same time.
+    ///
+    /// If `WASMER_MAX_CONCURRENCY` is set, the smaller of the two wins.
+    #[clap(long, conflicts_with = "example", value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    pub concurrency: u16,
+
+    /// The confirmation token required to export secrets when `WASMER_SECRETS_REVEAL_TOKENS` is
+    /// set, which must be one of the tokens it lists.
+    #[clap(long, conflicts_with = "example", value_name = "TOKEN")]
+    pub confirm_token: Option<String>,
+}
+
+/// The formats the secrets can be exported in.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Dotenv,
+    Json,
+    Yaml,
+}
+
+impl CmdAppSecretsExport {
+    /// Exporting is always non-interactive, and reveals all values: it goes through the same
+    /// gate as `reveal --all --non-interactive`, with the given allowlist. The example only has
+    /// the names.
+    fn check_reveal_policy(
+        &self,
+        allowed: Option<&str>,
+        warnings: &mut utils::warnings::Warnings,
+    ) -> Result<(), ExitCodeError> {
+        if self.example {
+            return Ok(());
+        }
+        utils::policy::check_bulk_reveal_against(allowed, self.confirm_token.as_deref(), warnings)
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncCliCommand for CmdAppSecretsExport {
+    type Output = ();
+
+    async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
+        let mut warnings = utils::warnings::Warnings::default();
+        let allowed = std::env::var(utils::policy::REVEAL_TOKENS_ENV).ok();
+        self.check_reveal_policy(allowed.as_deref(), &mut warnings)?;
+        warnings.print();
+
+        if !self.example && !self.force && std::io::stdout().is_terminal() {
+            anyhow::bail!(
+                "Refusing to write secrets to a terminal. Redirect the output or pass `--force`."
+            );
+        }
+
+        let baseline = match &self.only_changed {
+            Some(path) => Some(utils::read_secrets_from_any_file(path).await?),
+            None => None,
+        };
+
+        let client = self.env.client()?;
+        let app_id = utils::get_app_id(
+            &client,
+            self.app_id.app.as_ref(),
+            self.app_dir_path.as_ref(),
+            true,
+            true,
+            false,
+        )
+        .await?;
+
+        if self.example {
+            let secrets = utils::get_secrets(&client, &app_id).await?;
+            print!(
+                "{}",
+                utils::render::render_example(secrets.iter().map(|s| s.name.as_str()))
+            );
+            return Ok(());
+        }
+
+        let secrets = utils::reveal_secrets(&client, &app_id, self.concurrency.into())
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let secrets = self.value_filter.apply(secrets).await?;
+        let secrets = match &baseline {
+            Some(baseline) => utils::changed_secrets(secrets, baseline),
+            None => secrets,
+        };
+
+        match self.format {
+            ExportFormat::Dotenv => print!("{}", utils::render::render_dotenv(&secrets)),
+            ExportFormat::Json | ExportFormat::Yaml => {
+                let format = if self.format == ExportFormat::Json {
+                    ListFormat::Json
+                } else {
+                    ListFormat::Yaml
+                };
+                let version = self.output_version.version();
+                println!(
+                    "{}",
+                    utils::render::render_versioned(format, version, &secrets)?
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_export_requires_confirm_token() {
+        let check = |extra: &[&str]| {
+            let args = ["export", "--app", "my-org/my-app"].iter().chain(extra);
+            CmdAppSecretsExport::try_parse_from(args)
+                .unwrap()
+                .check_reveal_policy(Some("alpha"), &mut Default::default())
+        };
+
+        assert_eq!(check(&[]).unwrap_err().code, utils::policy::EXIT_CODE_USAGE);
+        assert_eq!(
+            check(&["--confirm-token", "beta"]).unwrap_err().code,
+            utils::policy::EXIT_CODE_USAGE
+        );
+        assert!(check(&["--confirm-token", "alpha"]).is_ok());
+        // The example has no values, so it needs no token.
+        assert!(check(&["--example"]).is_ok());
+    }
+}
@@ -1,4 +1,11 @@
-use super::utils::{get_secrets, BackendSecretWrapper};
+use super::utils::{
+    get_secrets,
+    metadata::MetadataCheck,
+    render::{is_structured, render_versioned, OutputVersionOpts},
+    warnings::Warnings,
+    window::UpdateWindowOpts,
+    BackendSecretWrapper,
+};
 use crate::{
     commands::{app::util::AppIdentFlag, AsyncCliCommand},
     config::WasmerEnv,
@@ -9,6 +16,7 @@ use std::path::PathBuf;
 
 /// Retrieve the value of an existing app secret.
 #[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::LIST))]
 pub struct CmdAppSecretsList {
     /* --- Common flags --- */
     #[clap(flatten)]
@@ -33,6 +41,28 @@ pub struct CmdAppSecretsList {
     /// The path to the directory where the config file for the application will be written to.
     #[clap(long = "app-dir", conflicts_with = "app")]
     pub app_dir_path: Option<PathBuf>,
+
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
+    /// Sort names case-insensitively, instead of in byte order.
+    ///
+    /// The default byte order gives the same output regardless of the locale.
+    #[clap(long)]
+    pub locale_sort: bool,
+
+    #[clap(flatten)]
+    pub window: UpdateWindowOpts,
+
+    /// Fail if a secret lacks the metadata a flag needs (e.g. the update time for `--since`),
+    /// instead of falling back with a warning.
+    #[clap(long)]
+    pub strict: bool,
+
+    #[clap(flatten)]
+    pub output_version: OutputVersionOpts,
 }
 
 #[async_trait::async_trait]
@@ -40,6 +70,8 @@ impl AsyncCliCommand for CmdAppSecretsList {
     type Output = ();
 
     async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
+        self.window.validate()?;
+
         let client = self.env.client()?;
         let app_id = super::utils::get_app_id(
             &client,
@@ -47,16 +79,32 @@ impl AsyncCliCommand for CmdAppSecretsList {
             self.app_dir_path.as_ref(),
             self.quiet,
             self.non_interactive,
+            self.guess_from_git,
         )
         .await?;
 
-        let secrets: Vec<BackendSecretWrapper> = get_secrets(&client, &app_id)
-            .await?
-            .into_iter()
-            .map(|s| s.into())
-            .collect();
+        let check = MetadataCheck::new(self.strict);
+        let mut secrets = Vec::new();
+        for secret in get_secrets(&client, &app_id).await? {
+            if self.window.contains(&secret, &check)? {
+                secrets.push(BackendSecretWrapper::from(secret));
+            }
+        }
+        super::utils::render::sort_by_name(&mut secrets, |s| &s.0.name, self.locale_sort);
+
+        let version = self.output_version.version();
+        let format = self.fmt.get(&self.env);
+        if is_structured(format) {
+            println!("{}", render_versioned(format, version, &secrets)?);
+        } else {
+            println!("{}", format.render(secrets.as_slice()));
+        }
 
-        println!("{}", self.fmt.format.render(secrets.as_slice()));
+        if !self.quiet {
+            let mut warnings = Warnings::default();
+            check.report(&mut warnings);
+            warnings.print();
+        }
 
         Ok(())
     }
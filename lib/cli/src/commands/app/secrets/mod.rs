@@ -1,20 +1,48 @@
 use crate::commands::AsyncCliCommand;
+use clap::CommandFactory;
 
+pub mod access_log;
+mod capabilities;
+pub mod copy;
 pub mod create;
 pub mod delete;
+mod examples;
+pub mod exists;
+pub mod export;
 pub mod list;
 pub mod reveal;
+pub mod rotate;
 pub mod update;
 mod utils;
 
 /// Manage and reveal secrets related to Edge apps.
 #[derive(Debug, clap::Parser)]
-pub enum CmdAppSecrets {
+#[clap(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+pub struct CmdAppSecrets {
+    /// Print a JSON document describing the available subcommands and their flags, for tools
+    /// wrapping the CLI.
+    #[clap(long)]
+    pub capabilities: bool,
+
+    #[clap(subcommand)]
+    pub cmd: Option<CmdAppSecretsSubcommand>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum CmdAppSecretsSubcommand {
+    #[clap(name = "cp", alias = "copy")]
+    Copy(copy::CmdAppSecretsCopy),
     Create(create::CmdAppSecretsCreate),
     Delete(delete::CmdAppSecretsDelete),
+    Exists(exists::CmdAppSecretsExists),
+    Export(export::CmdAppSecretsExport),
     Reveal(reveal::CmdAppSecretsReveal),
     List(list::CmdAppSecretsList),
+    Rotate(rotate::CmdAppSecretsRotate),
     Update(update::CmdAppSecretsUpdate),
+    #[clap(name = "tail-log", alias = "access-log")]
+    AccessLog(access_log::CmdAppSecretsAccessLog),
 }
 
 #[async_trait::async_trait]
@@ -22,25 +50,52 @@ impl AsyncCliCommand for CmdAppSecrets {
     type Output = ();
 
     async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
-        match self {
-            CmdAppSecrets::Create(c) => {
+        // Without a subcommand, clap only accepts `--capabilities`.
+        let Some(cmd) = self.cmd else {
+            let caps = capabilities::Capabilities::from_command(&Self::command());
+            println!("{}", serde_json::to_string_pretty(&caps)?);
+            return Ok(());
+        };
+
+        match cmd {
+            CmdAppSecretsSubcommand::Copy(c) => {
+                c.run_async().await?;
+                Ok(())
+            }
+            CmdAppSecretsSubcommand::Create(c) => {
+                c.run_async().await?;
+                Ok(())
+            }
+            CmdAppSecretsSubcommand::Delete(c) => {
                 c.run_async().await?;
                 Ok(())
             }
-            CmdAppSecrets::Delete(c) => {
+            CmdAppSecretsSubcommand::Exists(c) => {
                 c.run_async().await?;
                 Ok(())
             }
-            CmdAppSecrets::Reveal(c) => {
+            CmdAppSecretsSubcommand::Export(c) => {
                 c.run_async().await?;
                 Ok(())
             }
-            CmdAppSecrets::List(c) => {
+            CmdAppSecretsSubcommand::Reveal(c) => {
+                c.run_async().await?;
+                Ok(())
+            }
+            CmdAppSecretsSubcommand::List(c) => {
+                c.run_async().await?;
+                Ok(())
+            }
+            CmdAppSecretsSubcommand::Rotate(c) => {
                 c.run_async().await?;
                 Ok(())
             }
 
-            CmdAppSecrets::Update(c) => {
+            CmdAppSecretsSubcommand::Update(c) => {
+                c.run_async().await?;
+                Ok(())
+            }
+            CmdAppSecretsSubcommand::AccessLog(c) => {
                 c.run_async().await?;
                 Ok(())
             }
@@ -1,16 +1,30 @@
-use super::utils;
+use super::utils::{
+    self,
+    metadata::MetadataCheck,
+    output::OutputOpts,
+    render::{MaskOpts, OutputVersionOpts, RevealedSecret},
+    signing::SigningOpts,
+    warnings::Warnings,
+    window::UpdateWindowOpts,
+};
 use crate::{
     commands::{app::util::AppIdentFlag, AsyncCliCommand},
     config::WasmerEnv,
+    error::ExitCodeError,
     opts::ListFormatOpts,
     utils::render::{ItemFormat, ListFormat},
 };
+use anyhow::Context;
+use colored::Colorize;
 use dialoguer::theme::ColorfulTheme;
 use is_terminal::IsTerminal;
-use std::path::PathBuf;
+use std::{io::Write, path::PathBuf};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use wasmer_backend_api::WasmerClient;
 
 /// Reveal the value of an existing app secret.
 #[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::REVEAL))]
 pub struct CmdAppSecretsReveal {
     /* --- Common flags --- */
     #[clap(flatten)]
@@ -35,17 +49,181 @@ pub struct CmdAppSecretsReveal {
     #[clap(long = "app-dir", conflicts_with = "app")]
     pub app_dir_path: Option<PathBuf>,
 
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
     /// Reveal all the secrets related to an app.
     #[clap(long, conflicts_with = "name")]
     pub all: bool,
 
+    /// List the names of the secrets set on the app, without fetching any value.
+    ///
+    /// NOTE: whether a secret is populated cannot be checked without fetching its value, so this
+    /// does not tell empty values apart. Use `--all` to check the values themselves.
+    #[clap(long, conflicts_with_all = ["name", "all", "watch"])]
+    pub presence: bool,
+
+    /// Produce byte-stable output: secrets are deduplicated and sorted by name, values are always
+    /// quoted and escaped, and lines end with a single `\n`.
+    ///
+    /// Useful when the output is captured and diffed across runs.
+    #[clap(long, requires = "all")]
+    pub canonical: bool,
+
+    /// Sort names case-insensitively, instead of in byte order.
+    ///
+    /// The default byte order gives the same output regardless of the locale.
+    #[clap(long, requires = "all", conflicts_with = "canonical")]
+    pub locale_sort: bool,
+
+    #[clap(flatten)]
+    pub mask: MaskOpts,
+
+    #[clap(flatten)]
+    pub output: OutputOpts,
+
+    #[clap(flatten)]
+    pub output_version: OutputVersionOpts,
+
+    #[clap(flatten)]
+    pub signing: SigningOpts,
+
+    // Only used with `--all` or `--presence`.
+    #[clap(flatten)]
+    pub window: UpdateWindowOpts,
+
+    /// Report how the app was resolved: on stderr for human output, or as a `resolution` object
+    /// next to the secrets for structured formats (json, yaml).
+    #[clap(long)]
+    pub explain: bool,
+
+    /// Wrap the secrets of structured formats (json, yaml) in an object with metadata: the app,
+    /// when the secrets were fetched and how many there are.
+    ///
+    /// By default, the secrets are rendered as a bare list.
+    #[clap(long, requires = "all")]
+    pub envelope: bool,
+
+    /// Reveal the secrets as they were for the given app version id, instead of the current ones.
+    #[clap(long, requires = "all")]
+    pub version: Option<String>,
+
+    /// Only reveal the secrets that are missing from, or have a different value than in, the
+    /// given baseline file (dotenv, or a flat JSON object if the file ends in `.json`).
+    #[clap(long, value_name = "BASELINE", requires = "all")]
+    pub only_changed: Option<PathBuf>,
+
+    /// Truncate values longer than the given number of bytes in human-readable output.
+    ///
+    /// Structured formats (json, yaml) never truncate: they fail instead, unless `--no-truncate`
+    /// is passed.
+    #[clap(long, value_name = "N")]
+    pub max_value_bytes: Option<usize>,
+
+    /// Print values in full, even if they are longer than `--max-value-bytes`.
+    #[clap(long, requires = "max_value_bytes")]
+    pub no_truncate: bool,
+
+    /// Keep polling the secret, and print its value with a timestamp whenever it changes.
+    ///
+    /// Stop with Ctrl-C.
+    #[clap(long, conflicts_with_all = ["all", "format", "explain"])]
+    pub watch: bool,
+
+    /// How often to poll the secret with `--watch`.
+    #[clap(long, requires = "watch", default_value = "5s")]
+    pub interval: humantime::Duration,
+
+    /// With `--watch`, print the value after every poll, not only when it changes.
+    #[clap(long, requires = "watch")]
+    pub print_all: bool,
+
+    /// Fail if the value of any secret cannot be revealed, or if a secret lacks the metadata a
+    /// flag needs (e.g. the update time for `--since`).
+    ///
+    /// By default, unreadable secrets are reported as warnings, or as entries with an `error`
+    /// instead of a `value` for structured formats (json, yaml), and secrets without the needed
+    /// metadata are kept with a warning.
+    #[clap(long)]
+    pub strict: bool,
+
+    /// How many secret values to fetch at the same time.
+    ///
+    /// If `WASMER_MAX_CONCURRENCY` is set, the smaller of the two wins.
+    #[clap(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    pub concurrency: u16,
+
+    /// The confirmation token required to reveal all secrets non-interactively when
+    /// `WASMER_SECRETS_REVEAL_TOKENS` is set, which must be one of the tokens it lists.
+    #[clap(long, requires = "all", value_name = "TOKEN")]
+    pub confirm_token: Option<String>,
+
+    /// Fail if human-oriented output (no `--format`, or a table format) would not be written to a
+    /// terminal, so that scripts must pick a structured format explicitly.
+    #[clap(long)]
+    pub require_tty: bool,
+
+    /// Only check that the given secret exists, e.g. as a deploy gate, and exit with 4 if it
+    /// does not. Nothing is revealed.
+    #[clap(long, value_name = "NAME", conflicts_with_all = ["name", "all", "presence", "watch"])]
+    pub assert_name: Option<String>,
+
+    /// With `--assert-name`, also check that the SHA-256 checksum of the value of the secret is
+    /// the given one, in hexadecimal (as printed by `sha256sum`).
+    #[clap(long, value_name = "SHA256", requires = "assert_name", value_parser = utils::parse_checksum)]
+    pub assert_hash: Option<String>,
+
     /* --- Parameters --- */
     /// The name of the secret to get the value of.
     #[clap(name = "name")]
     pub secret_name: Option<String>,
 }
 
+/// The exit code used when an `--assert-name`/`--assert-hash` check or a `--verify` fails.
+pub(crate) const EXIT_CODE_ASSERTION_FAILED: i32 = 4;
+
 impl CmdAppSecretsReveal {
+    /// Check the `--assert-name` and `--assert-hash` preconditions.
+    async fn check_assertions(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        name: &str,
+    ) -> anyhow::Result<()> {
+        let Some(secret) = utils::get_secret_by_name(client, app_id, name).await? else {
+            return Err(ExitCodeError::new(
+                EXIT_CODE_ASSERTION_FAILED,
+                format!("Assertion failed: secret '{name}' does not exist."),
+            )
+            .into());
+        };
+
+        if let Some(expected) = &self.assert_hash {
+            let value = utils::get_secret_value(client, &secret).await?;
+            if !utils::values_eq(&utils::value_checksum(&value), expected) {
+                return Err(ExitCodeError::new(
+                    EXIT_CODE_ASSERTION_FAILED,
+                    format!(
+                        "Assertion failed: the value of secret '{name}' does not match the expected checksum."
+                    ),
+                )
+                .into());
+            }
+        }
+
+        if !self.quiet {
+            let matching = if self.assert_hash.is_some() {
+                " and matches the expected checksum"
+            } else {
+                ""
+            };
+            eprintln!("Secret '{name}' exists{matching}.");
+        }
+        Ok(())
+    }
+
     fn get_secret_name(&self) -> anyhow::Result<String> {
         if let Some(name) = &self.secret_name {
             return Ok(name.clone());
@@ -60,6 +238,121 @@ impl CmdAppSecretsReveal {
                 .interact_text()?)
         }
     }
+
+    /// In interactive mode, find the secret the user meant if there is no secret named `name`:
+    /// a unique similar name is used directly, otherwise the user picks among the candidates.
+    async fn match_secret_name(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        name: String,
+    ) -> anyhow::Result<String> {
+        if self.non_interactive
+            || utils::get_secret_by_name(client, app_id, &name)
+                .await?
+                .is_some()
+        {
+            return Ok(name);
+        }
+
+        let names: Vec<String> = utils::get_secrets(client, app_id)
+            .await?
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        let candidates = utils::similar_names(&name, &names);
+        match candidates.as_slice() {
+            [] => anyhow::bail!("No secret found with name {name} for app {app_id}"),
+            [candidate] => {
+                if !self.quiet {
+                    eprintln!(
+                        "No secret named '{}', using '{}' instead.",
+                        name,
+                        candidate.bold()
+                    );
+                }
+                Ok(candidate.to_string())
+            }
+            candidates => {
+                let theme = ColorfulTheme::default();
+                let selection = dialoguer::Select::with_theme(&theme)
+                    .with_prompt(format!("No secret named '{name}'. Did you mean"))
+                    .items(candidates)
+                    .default(0)
+                    .interact()?;
+                Ok(candidates[selection].to_string())
+            }
+        }
+    }
+
+    /// Poll the value of a secret, printing it with a timestamp whenever it changes (or after
+    /// every poll with `--print-all`), until interrupted.
+    async fn watch(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        name: &str,
+        out: &mut (dyn Write + Send),
+    ) -> anyhow::Result<()> {
+        let mut last: Option<String> = None;
+
+        loop {
+            match utils::get_secret_value_by_name(client, app_id, name).await {
+                Ok(value) => {
+                    let changed = last
+                        .as_deref()
+                        .map_or(true, |last| !utils::values_eq(last, &value));
+                    if changed || self.print_all {
+                        let mut secret = utils::Secret {
+                            name: name.to_string(),
+                            value: value.clone(),
+                        };
+                        if let Some(policy) = self.mask.policy() {
+                            secret.value = policy.mask(&secret.value);
+                        }
+                        self.limit_value(&mut secret, false)?;
+
+                        let now = OffsetDateTime::now_utc().format(&Rfc3339)?;
+                        writeln!(
+                            out,
+                            "{now} {}",
+                            utils::render::sanitize_value(&secret.value)
+                        )?;
+                        out.flush()?;
+                    }
+                    last = Some(value);
+                }
+                // Only give up if the secret could never be read.
+                Err(err) if last.is_none() => return Err(err),
+                Err(err) => eprintln!("{}: {err:#}", "Warning".bold().yellow()),
+            }
+
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+                _ = tokio::time::sleep(self.interval.into()) => {}
+            }
+        }
+    }
+
+    /// Apply `--max-value-bytes` to the value of a secret about to be printed.
+    fn limit_value(&self, secret: &mut utils::Secret, structured: bool) -> anyhow::Result<()> {
+        let Some(max) = self.max_value_bytes else {
+            return Ok(());
+        };
+        if self.no_truncate || secret.value.len() <= max {
+            return Ok(());
+        }
+
+        if structured {
+            anyhow::bail!(
+                "The value of secret '{}' is {} bytes long, more than --max-value-bytes ({max}). Pass --no-truncate to print it in full.",
+                secret.name.bold(),
+                secret.value.len()
+            );
+        }
+        secret.value = utils::render::truncate_value(&secret.value, max);
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -67,25 +360,136 @@ impl AsyncCliCommand for CmdAppSecretsReveal {
     type Output = ();
 
     async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
+        if let Some(path) = &self.signing.verify {
+            if !self.signing.verify_file(path)? {
+                return Err(ExitCodeError::new(
+                    EXIT_CODE_ASSERTION_FAILED,
+                    format!(
+                        "Signature verification failed: '{}' was not signed with the given key, or was modified since.",
+                        path.display()
+                    ),
+                )
+                .into());
+            }
+            if !self.quiet {
+                eprintln!("The signature of '{}' is valid.", path.display());
+            }
+            return Ok(());
+        }
+
+        let mut warnings = Warnings::default();
+        // Whether the warnings are part of the structured output.
+        let mut warnings_in_output = false;
+        let metadata_check = MetadataCheck::new(self.strict);
+
+        if self.all && self.non_interactive {
+            utils::policy::check_bulk_reveal(self.confirm_token.as_deref(), &mut warnings)?;
+        }
+
+        self.window.validate()?;
+        if self.window.is_set() && !self.all && !self.presence {
+            anyhow::bail!("`--since` and `--until` can only be used with `--all` or `--presence`.");
+        }
+
+        let list_format = self.fmt.as_ref().map(|f| f.get(&self.env));
+        let human_output = list_format.map_or(true, |f| {
+            matches!(f, ListFormat::Table | ListFormat::ItemTable)
+        });
+        if self.require_tty && human_output && !self.output.is_terminal() {
+            return Err(ExitCodeError::new(
+                utils::policy::EXIT_CODE_USAGE,
+                "The output is not a terminal, but no structured format was selected (`--require-tty`). Pass `--format json` or `--format yaml`.",
+            )
+            .into());
+        }
+
+        // Opened first, so that a bad `--out`/`--out-fd` or signing key fails before any
+        // secret is read.
+        let signing_key = self.signing.signing_key()?;
+        if signing_key.is_some() && self.output.compression().is_some() {
+            anyhow::bail!("`--sign` cannot be used with compressed output.");
+        }
+        let mut out = self.output.open()?;
+        // The detached signature of the output, written once the output is complete.
+        let mut signature = None;
+
         let client = self.env.client()?;
-        let app_id = super::utils::get_app_id(
+        let resolution = super::utils::resolve_app(
             &client,
             self.app_id.app.as_ref(),
             self.app_dir_path.as_ref(),
             self.quiet,
             self.non_interactive,
+            self.guess_from_git,
         )
         .await?;
+        let app_id = resolution.app_id.clone();
+
+        if let Some(name) = &self.assert_name {
+            self.check_assertions(&client, &app_id, name).await?;
+            // Nothing was written, so an existing `--out` file is left as it is.
+            drop(out);
+            return Ok(());
+        }
 
-        if !self.all {
+        // Structured formats carry the resolution report in the output itself.
+        let explain_format =
+            list_format.filter(|f| self.explain && utils::render::is_structured(*f));
+        if self.explain && explain_format.is_none() {
+            eprintln!("{}", resolution.describe());
+        }
+        let version = self.output_version.version();
+        let structured_format =
+            explain_format.or_else(|| list_format.filter(|f| utils::render::is_structured(*f)));
+
+        if self.presence {
+            let mut items = Vec::new();
+            for secret in utils::get_secrets(&client, &app_id).await? {
+                if self.window.contains(&secret, &metadata_check)? {
+                    items.push(utils::render::SecretPresence::from(&secret));
+                }
+            }
+            metadata_check.report(&mut warnings);
+            utils::render::sort_by_name(&mut items, |s| &s.name, false);
+            let rendered = match structured_format {
+                Some(format) => utils::render::render_versioned(format, version, &items)?,
+                None => {
+                    let format = list_format.unwrap_or(ListFormat::Table);
+                    format.render(&items)
+                }
+            };
+            writeln!(out, "{rendered}")?;
+        } else if !self.all {
             let name = self.get_secret_name()?;
+            let name = self.match_secret_name(&client, &app_id, name).await?;
+            if self.watch {
+                let res = self.watch(&client, &app_id, &name, &mut out).await;
+                out.finish()?;
+                return res;
+            }
 
-            let value = utils::get_secret_value_by_name(&client, &app_id, &name).await?;
+            let mut value = utils::get_secret_value_by_name(&client, &app_id, &name).await?;
+            if let Some(policy) = self.mask.policy() {
+                value = policy.mask(&value);
+            }
 
-            let secret = utils::Secret { name, value };
+            let mut secret = utils::Secret { name, value };
+            self.limit_value(&mut secret, structured_format.is_some())?;
 
-            if let Some(fmt) = &self.fmt {
-                let fmt = match fmt.format {
+            if let Some(format) = explain_format {
+                let rendered = utils::render::render_with_resolution(
+                    format,
+                    version,
+                    "secret",
+                    &secret,
+                    &resolution,
+                )?;
+                writeln!(out, "{rendered}")?;
+            } else if let Some(format) = structured_format {
+                let rendered = utils::render::render_versioned(format, version, &secret)?;
+                writeln!(out, "{rendered}")?;
+            } else if let Some(fmt) = list_format {
+                let fmt = match fmt {
                     ListFormat::Json => ItemFormat::Json,
                     ListFormat::Yaml => ItemFormat::Yaml,
                     ListFormat::Table => ItemFormat::Table,
@@ -93,26 +497,151 @@ impl AsyncCliCommand for CmdAppSecretsReveal {
                         anyhow::bail!("The 'item-table' format is not available for single values.")
                     }
                 };
-                println!("{}", fmt.render(&secret));
+                writeln!(out, "{}", fmt.render(&secret))?;
             } else {
-                print!("{}", secret.value);
+                // The raw value is printed as is, so an empty one would look like nothing
+                // was printed at all.
+                if secret.value.is_empty() && !self.quiet {
+                    eprintln!(
+                        "{}: secret '{}' is set, but its value is empty.",
+                        "Note".bold(),
+                        secret.name.bold()
+                    );
+                }
+                write!(out, "{}", secret.value)?;
             }
         } else {
-            let secrets: Vec<utils::Secret> = utils::reveal_secrets(&client, &app_id).await?;
+            if self.envelope && structured_format.is_none() {
+                anyhow::bail!("`--envelope` is only available for the json and yaml formats.");
+            }
+
+            let baseline = match &self.only_changed {
+                Some(path) => Some(utils::read_secrets_from_any_file(path).await?),
+                None => None,
+            };
 
-            if let Some(fmt) = &self.fmt {
-                println!("{}", fmt.format.render(secrets.as_slice()));
+            let mut secrets = Vec::new();
+            let mut errors = Vec::new();
+            if let Some(version) = &self.version {
+                utils::check_versioned_secrets(&client, &app_id, version).await?;
+            }
+            let fetched_at = OffsetDateTime::now_utc();
+            for res in utils::reveal_secrets_where(&client, &app_id, self.concurrency.into(), |s| {
+                self.window.contains(s, &metadata_check)
+            })
+            .await?
+            {
+                match res {
+                    Ok(secret) => secrets.push(secret),
+                    Err(err) => errors.push(err),
+                }
+            }
+            metadata_check.report(&mut warnings);
+            if self.strict && !errors.is_empty() {
+                let count = errors.len();
+                return Err(anyhow::Error::new(errors.swap_remove(0))
+                    .context(format!("{count} secret(s) could not be revealed")));
+            }
+
+            if let Some(baseline) = &baseline {
+                secrets = utils::changed_secrets(secrets, baseline);
+            }
+            if self.canonical {
+                secrets = utils::render::canonicalize(secrets);
             } else {
-                for secret in secrets {
-                    println!(
-                        "{}=\"{}\"",
-                        secret.name,
-                        utils::render::sanitize_value(&secret.value)
-                    );
+                utils::render::sort_by_name(&mut secrets, |s| &s.name, self.locale_sort);
+            }
+            if let Some(policy) = self.mask.policy() {
+                for secret in &mut secrets {
+                    secret.value = policy.mask(&secret.value);
+                }
+            }
+
+            for secret in &mut secrets {
+                self.limit_value(secret, structured_format.is_some())?;
+            }
+
+            let rendered = if let Some(format) = structured_format {
+                let items: Vec<RevealedSecret> = secrets
+                    .iter()
+                    .map(RevealedSecret::from)
+                    .chain(errors.iter().map(RevealedSecret::from))
+                    .collect();
+                let out = if self.envelope {
+                    let mut envelope =
+                        utils::render::Envelope::new(&resolution, fetched_at, &items)?;
+                    if explain_format.is_some() {
+                        envelope.resolution = Some(&resolution);
+                    }
+                    envelope.warnings = warnings.as_slice();
+                    warnings_in_output = true;
+                    utils::render::render_versioned(format, version, &envelope)
+                } else if explain_format.is_some() {
+                    utils::render::render_with_resolution(
+                        format,
+                        version,
+                        "secrets",
+                        &items,
+                        &resolution,
+                    )
+                } else {
+                    utils::render::render_versioned(format, version, &items)
+                };
+                format!("{}\n", out?)
+            } else {
+                for err in &errors {
+                    warnings.push_for_secret("unreadable_secret", &err.name, err.to_string());
+                }
+                if let Some(fmt) = list_format {
+                    format!("{}\n", fmt.render(secrets.as_slice()))
+                } else {
+                    utils::render::render_dotenv(&secrets)
                 }
+            };
+
+            if self.canonical {
+                let payload = utils::render::normalize_line_endings(&rendered);
+                write!(out, "{payload}")?;
+                signature = signing_key.map(|key| key.sign(payload.as_bytes()));
+            } else {
+                write!(out, "{rendered}")?;
+            }
+
+            if structured_format.is_none() && !self.quiet {
+                let mut summary = format!(
+                    "Revealed {} secret(s) for app {}",
+                    secrets.len(),
+                    resolution.app_label()
+                );
+                if !errors.is_empty() {
+                    summary.push_str(&format!(", {} could not be revealed", errors.len()));
+                }
+                if self.explain {
+                    summary.push_str(&format!(
+                        ", resolved from {}",
+                        resolution.source_description()
+                    ));
+                }
+                eprintln!("{summary}.");
             }
         }
 
+        out.finish()?;
+        if let (Some(signature), Some(path)) = (signature, &self.output.out) {
+            let signature_path = self.signing.signature_path(path);
+            std::fs::write(&signature_path, signature).with_context(|| {
+                format!(
+                    "could not write the signature to '{}'",
+                    signature_path.display()
+                )
+            })?;
+            if !self.quiet {
+                eprintln!("Wrote the signature to '{}'.", signature_path.display());
+            }
+        }
+        if !warnings_in_output {
+            warnings.print();
+        }
         Ok(())
     }
 }
@@ -0,0 +1,303 @@
+use crate::{
+    commands::{app::util::AppIdentFlag, AsyncCliCommand},
+    config::WasmerEnv,
+    opts::ListFormatOpts,
+    utils::render::CliRender,
+};
+use anyhow::Context;
+use colored::Colorize;
+use comfy_table::{Cell, Table};
+use dialoguer::theme::ColorfulTheme;
+use is_terminal::IsTerminal;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use regex::Regex;
+use std::path::PathBuf;
+use wasmer_backend_api::WasmerClient;
+
+use super::utils::{self, batch};
+
+/// Replace the value of app secrets with newly generated random values.
+///
+/// The backend does not record which secrets may be rotated, so every selected secret is:
+/// use `--exclude` to keep the ones that must not change (e.g. credentials of external
+/// services). The previous values are not backed up.
+///
+/// The report gives the SHA-256 checksum of each new value, which can be checked later with
+/// `wasmer app secret reveal --assert-name NAME --assert-hash CHECKSUM`.
+#[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::ROTATE))]
+pub struct CmdAppSecretsRotate {
+    /* --- Common flags --- */
+    #[clap(flatten)]
+    pub env: WasmerEnv,
+
+    /// Don't print any message.
+    #[clap(long)]
+    pub quiet: bool,
+
+    /// Do not prompt for user input.
+    #[clap(long, default_value_t = !std::io::stdin().is_terminal())]
+    pub non_interactive: bool,
+
+    /// The format of the per-secret report.
+    #[clap(flatten)]
+    pub fmt: Option<ListFormatOpts>,
+
+    /* --- Flags --- */
+    #[clap(flatten)]
+    pub app_id: AppIdentFlag,
+
+    /// The path to the directory where the config file for the application will be written to.
+    #[clap(long = "app-dir", conflicts_with = "app")]
+    pub app_dir_path: Option<PathBuf>,
+
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
+    /// Rotate all the secrets of the app.
+    #[clap(long, conflicts_with = "name")]
+    pub all: bool,
+
+    /// With `--all`, skip the secrets whose name matches the given pattern, where `*` matches
+    /// any number of characters and `?` a single one (e.g. `DB_*`). Can be repeated.
+    #[clap(long, value_name = "PATTERN", requires = "all")]
+    pub exclude: Vec<String>,
+
+    /// Rotate the secret(s) without asking for confirmation. Required in non-interactive mode.
+    ///
+    /// The previous values are not fetched nor backed up: they are lost once rotated.
+    #[clap(long)]
+    pub yes: bool,
+
+    /// The number of characters of the generated values.
+    #[clap(long, default_value_t = 32, value_parser = clap::value_parser!(u16).range(16..))]
+    pub length: u16,
+
+    /* --- Parameters --- */
+    /// The name of the secret to rotate.
+    #[clap(name = "name")]
+    pub secret_name: Option<String>,
+}
+
+/// The outcome of the rotation of a single secret.
+///
+/// The new value is only identified by its checksum, which tells nothing about
+/// a random value.
+#[derive(Debug, serde::Serialize)]
+struct RotatedSecret {
+    name: String,
+    /// The SHA-256 checksum of the new value, if the rotation succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checksum: Option<String>,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl CmdAppSecretsRotate {
+    fn confirm(&self, app_id: &str, count: usize) -> anyhow::Result<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+        if self.non_interactive {
+            anyhow::bail!(
+                "Refusing to rotate secrets in non-interactive mode without the `--yes` flag."
+            )
+        }
+        let theme = ColorfulTheme::default();
+        Ok(dialoguer::Confirm::with_theme(&theme)
+            .with_prompt(format!(
+                "Replace the value of {count} secret(s) of app {}? The current values will be lost.",
+                app_id.bold()
+            ))
+            .default(false)
+            .interact()?)
+    }
+
+    async fn rotate(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        name: &str,
+        value: String,
+    ) -> anyhow::Result<()> {
+        let res = wasmer_backend_api::query::upsert_app_secret(client, app_id, name, value)
+            .await?
+            .context("Backend did not return any payload to confirm the update of the secret")?;
+        if !res.success {
+            anyhow::bail!("the backend did not update the secret");
+        }
+        Ok(())
+    }
+
+    fn print_report(&self, report: &[RotatedSecret]) {
+        if let Some(fmt) = &self.fmt {
+            println!("{}", fmt.get(&self.env).render(report));
+        } else if !self.quiet {
+            eprintln!("{}", RotatedSecret::render_list_table(report));
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncCliCommand for CmdAppSecretsRotate {
+    type Output = ();
+
+    async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
+        if self.secret_name.is_none() && !self.all {
+            anyhow::bail!(
+                "No secret name given. Provide one as a positional argument, or pass `--all`."
+            );
+        }
+        let excluded = self
+            .exclude
+            .iter()
+            .map(|p| glob_to_regex(p))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let client = utils::mutation_client(&self.env)?;
+        let app_id = utils::get_app_id(
+            &client,
+            self.app_id.app.as_ref(),
+            self.app_dir_path.as_ref(),
+            self.quiet,
+            self.non_interactive,
+            self.guess_from_git,
+        )
+        .await?;
+
+        let name = self.secret_name.as_deref();
+        // Only the names are needed: the current values are never fetched.
+        let secrets: Vec<_> = utils::get_secrets(&client, &app_id)
+            .await?
+            .into_iter()
+            .filter(|s| match name {
+                Some(name) => s.name == name,
+                None => !excluded.iter().any(|re| re.is_match(&s.name)),
+            })
+            .collect();
+
+        if secrets.is_empty() {
+            if let Some(name) = name {
+                anyhow::bail!("No secret found with name {name} for app {app_id}");
+            }
+            if !self.quiet {
+                eprintln!("No secrets to rotate.");
+            }
+            return Ok(());
+        }
+        if !self.confirm(&app_id, secrets.len())? {
+            return Ok(());
+        }
+
+        let mut report = Vec::with_capacity(secrets.len());
+        for secret in secrets {
+            let value = generate_value(self.length.into());
+            let checksum = utils::value_checksum(&value);
+            let entry = match self.rotate(&client, &app_id, &secret.name, value).await {
+                Ok(()) => RotatedSecret {
+                    name: secret.name,
+                    checksum: Some(checksum),
+                    success: true,
+                    error: None,
+                },
+                Err(err) => RotatedSecret {
+                    name: secret.name,
+                    checksum: None,
+                    success: false,
+                    error: Some(format!("{err:#}")),
+                },
+            };
+            report.push(entry);
+        }
+
+        self.print_report(&report);
+        let failed = report.iter().filter(|r| !r.success).count();
+        batch::outcome(failed, report.len(), "rotate")
+    }
+}
+
+/// A random alphanumeric value of `length` characters, from the OS generator.
+fn generate_value(length: usize) -> String {
+    OsRng
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// Compile a name pattern where `*` matches any number of characters and `?`
+/// a single one.
+fn glob_to_regex(pattern: &str) -> anyhow::Result<Regex> {
+    let re = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    Regex::new(&format!("^{re}$")).with_context(|| format!("invalid pattern '{pattern}'"))
+}
+
+impl CliRender for RotatedSecret {
+    fn render_item_table(&self) -> String {
+        let mut table = Table::new();
+        table.add_rows([
+            vec!["Name".to_string(), self.name.clone()],
+            vec![
+                "Checksum".to_string(),
+                self.checksum.clone().unwrap_or_default(),
+            ],
+            vec!["Success".to_string(), self.success.to_string()],
+            vec!["Error".to_string(), self.error.clone().unwrap_or_default()],
+        ]);
+        table.to_string()
+    }
+
+    fn render_list_table(items: &[Self]) -> String {
+        if items.is_empty() {
+            return String::new();
+        }
+        let mut table = Table::new();
+        table.load_preset(comfy_table::presets::NOTHING);
+        table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+
+        table.set_header(
+            ["Name", "Checksum", "Success", "Error"]
+                .map(|h| Cell::new(h).add_attribute(comfy_table::Attribute::Bold)),
+        );
+        table.add_rows(items.iter().map(|i| {
+            vec![
+                Cell::new(i.name.clone()),
+                Cell::new(i.checksum.clone().unwrap_or_default()),
+                Cell::new(i.success.to_string()),
+                Cell::new(i.error.clone().unwrap_or_default()),
+            ]
+        }));
+        table.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_to_regex() {
+        let re = glob_to_regex("DB_*").unwrap();
+        assert!(re.is_match("DB_URL"));
+        assert!(re.is_match("DB_"));
+        assert!(!re.is_match("OLD_DB_URL"));
+
+        let re = glob_to_regex("KEY_?.v1").unwrap();
+        assert!(re.is_match("KEY_A.v1"));
+        assert!(!re.is_match("KEY_AB.v1"));
+        assert!(!re.is_match("KEY_Axv1"));
+    }
+
+    #[test]
+    fn test_generate_value() {
+        let value = generate_value(32);
+        assert_eq!(value.len(), 32);
+        assert!(value.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(value, generate_value(32));
+    }
+}
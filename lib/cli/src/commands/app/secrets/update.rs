@@ -1,7 +1,8 @@
-use super::utils::Secret;
+use super::utils::{batch::BatchResults, rename::RenameOpts, Secret};
 use crate::{
     commands::{app::util::AppIdentFlag, AsyncCliCommand},
     config::WasmerEnv,
+    opts::ListFormatOpts,
 };
 use anyhow::Context;
 use colored::Colorize;
@@ -15,6 +16,7 @@ use wasmer_backend_api::WasmerClient;
 
 /// Update an existing app secret.
 #[derive(clap::Parser, Debug)]
+#[clap(after_help = super::examples::render(super::examples::UPDATE))]
 pub struct CmdAppSecretsUpdate {
     /* --- Common args --- */
     #[clap(flatten)]
@@ -28,11 +30,21 @@ pub struct CmdAppSecretsUpdate {
     #[clap(long, default_value_t = !std::io::stdin().is_terminal())]
     pub non_interactive: bool,
 
+    /// The format of the report printed when the input is invalid, and of the per-secret report
+    /// printed when updating multiple secrets.
+    #[clap(flatten)]
+    pub fmt: Option<ListFormatOpts>,
+
     /* --- Flags --- */
     /// The path to the directory where the config file for the application will be written to.
     #[clap(long = "app-dir", conflicts_with = "app")]
     pub app_dir_path: Option<PathBuf>,
 
+    /// If the app cannot be determined from the flags or an app config, try to guess it from
+    /// the git remote 'origin' of the current directory.
+    #[clap(long, conflicts_with = "app")]
+    pub guess_from_git: bool,
+
     #[clap(flatten)]
     pub app_id: AppIdentFlag,
 
@@ -45,10 +57,24 @@ pub struct CmdAppSecretsUpdate {
     )]
     pub from_file: Option<PathBuf>,
 
+    /// Allow secrets with an empty value.
+    ///
+    /// Empty values are rejected by default, as they are usually a mistake.
+    #[clap(long)]
+    pub allow_empty: bool,
+
+    #[clap(flatten)]
+    pub rename: RenameOpts,
+
     /// Whether or not to redeploy the app after creating the secrets.
     #[clap(long)]
     pub redeploy: bool,
 
+    /// When updating multiple secrets, abort on the first error instead of reporting the outcome
+    /// of each update.
+    #[clap(long)]
+    pub fail_fast: bool,
+
     /* --- Parameters --- */
     /// The name of the secret to update.
     #[clap(name = "name")]
@@ -92,12 +118,17 @@ impl CmdAppSecretsUpdate {
 
     /// Given a list of secrets, checks if the given secrets already exist for the given app and
     /// returns a list of secrets that must be upserted.
+    ///
+    /// Secrets that cannot be updated are returned as errors, so that they are reported along
+    /// with the others.
     async fn filter_secrets(
         &self,
         client: &WasmerClient,
         app_id: &str,
         secrets: Vec<Secret>,
-    ) -> anyhow::Result<Vec<Secret>> {
+    ) -> anyhow::Result<Vec<Result<Secret, (String, anyhow::Error)>>> {
+        super::utils::validation::validate_secret_values(&secrets, self.allow_empty)?;
+
         let names = secrets.iter().map(|s| &s.name);
         let app_secrets =
             wasmer_backend_api::query::get_all_app_secrets_filtered(client, app_id, names).await?;
@@ -108,7 +139,12 @@ impl CmdAppSecretsUpdate {
         for secret in secrets {
             if !sset.contains(secret.name.as_str()) {
                 if self.non_interactive {
-                    anyhow::bail!("Cannot update secret '{}' in app {app_id} as it does not exist yet. Use the `create` command instead.", secret.name.bold());
+                    let err = anyhow::anyhow!("Cannot update secret '{}' in app {app_id} as it does not exist yet. Use the `create` command instead.", secret.name.bold());
+                    if self.fail_fast {
+                        return Err(err);
+                    }
+                    ret.push(Err((secret.name, err)));
+                    continue;
                 } else {
                     eprintln!(
                         "Secret '{}' does not exist for the selected app.",
@@ -125,11 +161,12 @@ impl CmdAppSecretsUpdate {
                 }
             }
 
-            ret.push(secret);
+            ret.push(Ok(secret));
         }
 
         Ok(ret)
     }
+
     async fn update(
         &self,
         client: &WasmerClient,
@@ -148,37 +185,83 @@ impl CmdAppSecretsUpdate {
 
         if !res.success {
             anyhow::bail!("Secret creation failed!")
-        } else {
-            if !self.quiet {
-                eprintln!("Succesfully updated secret(s):");
-                for secret in &secrets {
-                    eprintln!("{}", secret.name.bold());
+        }
+        self.updated(client, app_id, secrets.iter().map(|s| s.name.as_str()))
+            .await
+    }
+
+    /// Update the secrets one by one, and report the outcome of each.
+    async fn update_many(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        secrets: Vec<Result<Secret, (String, anyhow::Error)>>,
+    ) -> anyhow::Result<()> {
+        let mut results = BatchResults::default();
+        let mut updated = Vec::new();
+
+        for secret in secrets {
+            let (name, res) = match secret {
+                Ok(secret) => {
+                    let res = update_one(client, app_id, &secret).await;
+                    (secret.name, res)
                 }
+                Err((name, err)) => (name, Err(err)),
+            };
+            if self.fail_fast {
+                res?;
+                updated.push(name);
+            } else {
+                if res.is_ok() {
+                    updated.push(name.clone());
+                }
+                results.push(name, res);
+            }
+        }
 
-                let should_redeploy = self.redeploy || {
-                    if !self.non_interactive && self.from_file.is_some() {
-                        let theme = ColorfulTheme::default();
-                        dialoguer::Confirm::with_theme(&theme)
-                            .with_prompt("Do you want to redeploy your app?")
-                            .interact()?
-                    } else {
-                        false
-                    }
-                };
+        if !updated.is_empty() {
+            self.updated(client, app_id, updated.iter().map(String::as_str))
+                .await?;
+        }
+        results.finish(self.fmt.as_ref().map(|f| f.get(&self.env)), "update")
+    }
+
+    /// Report the updated secrets, and redeploy the app if requested.
+    async fn updated(
+        &self,
+        client: &WasmerClient,
+        app_id: &str,
+        names: impl Iterator<Item = &str>,
+    ) -> anyhow::Result<()> {
+        if !self.quiet {
+            eprintln!("Succesfully updated secret(s):");
+            for name in names {
+                eprintln!("{}", name.bold());
+            }
 
-                if should_redeploy {
-                    wasmer_backend_api::query::redeploy_app_by_id(client, app_id).await?;
-                    eprintln!("{} Deployment complete", "𖥔".yellow().bold());
+            let should_redeploy = self.redeploy || {
+                if !self.non_interactive && self.from_file.is_some() {
+                    let theme = ColorfulTheme::default();
+                    dialoguer::Confirm::with_theme(&theme)
+                        .with_prompt("Do you want to redeploy your app?")
+                        .interact()?
                 } else {
-                    eprintln!(
-                        "{}: In order for secrets to appear in your app, re-deploy it.",
-                        "Info".bold()
-                    );
+                    false
                 }
-            }
+            };
 
-            Ok(())
+            if should_redeploy {
+                wasmer_backend_api::query::redeploy_app_by_id(client, app_id).await?;
+                eprintln!("{} Deployment complete", "𖥔".yellow().bold());
+            } else {
+                eprintln!(
+                    "{}: In order for secrets to appear in your app, re-deploy it.",
+                    "Info".bold()
+                );
+            }
         }
+
+        Ok(())
     }
 
     async fn update_from_file(
@@ -188,12 +271,22 @@ impl CmdAppSecretsUpdate {
         app_id: &str,
     ) -> anyhow::Result<(), anyhow::Error> {
         let secrets = super::utils::read_secrets_from_file(path).await?;
+        let secrets = self.rename.apply(secrets, self.quiet)?;
 
         let secrets = self.filter_secrets(client, app_id, secrets).await?;
-        self.update(client, app_id, secrets).await?;
+        self.update_many(client, app_id, secrets).await
+    }
+}
 
-        Ok(())
+async fn update_one(client: &WasmerClient, app_id: &str, secret: &Secret) -> anyhow::Result<()> {
+    let res =
+        wasmer_backend_api::query::upsert_app_secret(client, app_id, &secret.name, &secret.value)
+            .await?
+            .context("Backend did not return any payload to confirm the update of the secret")?;
+    if !res.success {
+        anyhow::bail!("the backend did not update the secret");
     }
+    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -201,13 +294,27 @@ impl AsyncCliCommand for CmdAppSecretsUpdate {
     type Output = ();
 
     async fn run_async(self) -> Result<Self::Output, anyhow::Error> {
-        let client = self.env.client()?;
+        let format = self.fmt.as_ref().map(|f| f.get(&self.env));
+        self.run()
+            .await
+            .map_err(|e| super::utils::validation::report(e, format))
+    }
+}
+
+impl CmdAppSecretsUpdate {
+    async fn run(self) -> anyhow::Result<()> {
+        if !self.rename.rules.is_empty() && self.from_file.is_none() {
+            anyhow::bail!("`--rename` can only be used with `--from-file`");
+        }
+
+        let client = super::utils::mutation_client(&self.env)?;
         let app_id = super::utils::get_app_id(
             &client,
             self.app_id.app.as_ref(),
             self.app_dir_path.as_ref(),
             self.quiet,
             self.non_interactive,
+            self.guess_from_git,
         )
         .await?;
 
@@ -216,8 +323,9 @@ impl AsyncCliCommand for CmdAppSecretsUpdate {
         } else {
             let name = self.get_secret_name()?;
             let value = self.get_secret_value()?;
-            let secret = Secret { name, value };
-            self.update(&client, &app_id, vec![secret]).await
+            let secrets = vec![Secret { name, value }];
+            super::utils::validation::validate_secret_values(&secrets, self.allow_empty)?;
+            self.update(&client, &app_id, secrets).await
         }
     }
 }
@@ -0,0 +1,144 @@
+use crate::{
+    error::ExitCodeError,
+    utils::render::{CliRender, ListFormat},
+};
+use colored::Colorize;
+use comfy_table::{Cell, Table};
+
+/// The exit code used when some, but not all, items of a batch operation failed.
+pub(crate) const EXIT_CODE_PARTIAL_FAILURE: i32 = 6;
+
+/// The outcome of a single item of a batch operation.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct BatchItem {
+    pub name: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Accumulates the per-item outcomes of a batch operation.
+#[derive(Debug, Default)]
+pub(crate) struct BatchResults {
+    items: Vec<BatchItem>,
+}
+
+impl BatchResults {
+    pub fn push(&mut self, name: impl Into<String>, result: anyhow::Result<()>) {
+        let (success, error) = match result {
+            Ok(()) => (true, None),
+            Err(e) => (false, Some(format!("{e:#}"))),
+        };
+        self.items.push(BatchItem {
+            name: name.into(),
+            success,
+            error,
+        });
+    }
+
+    pub fn failed(&self) -> usize {
+        self.items.iter().filter(|i| !i.success).count()
+    }
+
+    /// Print the outcome of each item (in the given format, if any) and return an error if any
+    /// of them failed (see [`outcome`]).
+    pub fn finish(self, format: Option<ListFormat>, action: &str) -> anyhow::Result<()> {
+        let failed = self.failed();
+        let total = self.items.len();
+
+        if let Some(format) = format {
+            println!("{}", format.render(self.items.as_slice()));
+        } else {
+            for item in &self.items {
+                if let Some(error) = &item.error {
+                    eprintln!(
+                        "{}: could not {action} secret '{}': {error}",
+                        "Error".bold().red(),
+                        item.name.bold()
+                    );
+                }
+            }
+        }
+
+        outcome(failed, total, action)
+    }
+}
+
+/// The result of a batch operation on `total` secrets, `failed` of which failed.
+///
+/// If only some of them failed, the error makes the CLI exit with
+/// [`EXIT_CODE_PARTIAL_FAILURE`].
+pub(crate) fn outcome(failed: usize, total: usize, action: &str) -> anyhow::Result<()> {
+    if failed == 0 {
+        Ok(())
+    } else if failed == total {
+        anyhow::bail!("Could not {action} any of the {total} secret(s)")
+    } else {
+        Err(ExitCodeError::new(
+            EXIT_CODE_PARTIAL_FAILURE,
+            format!("Could not {action} {failed} of {total} secret(s)"),
+        )
+        .into())
+    }
+}
+
+impl CliRender for BatchItem {
+    fn render_item_table(&self) -> String {
+        let mut table = Table::new();
+        table.add_rows([
+            vec!["Name".to_string(), self.name.clone()],
+            vec!["Success".to_string(), self.success.to_string()],
+            vec!["Error".to_string(), self.error.clone().unwrap_or_default()],
+        ]);
+        table.to_string()
+    }
+
+    fn render_list_table(items: &[Self]) -> String {
+        if items.is_empty() {
+            return String::new();
+        }
+        let mut table = Table::new();
+        table.load_preset(comfy_table::presets::NOTHING);
+        table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+
+        table.set_header(vec![
+            Cell::new("Name".to_string()).add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Success".to_string()).add_attribute(comfy_table::Attribute::Bold),
+            Cell::new("Error".to_string()).add_attribute(comfy_table::Attribute::Bold),
+        ]);
+        table.add_rows(items.iter().map(|i| {
+            vec![
+                Cell::new(i.name.clone()),
+                Cell::new(i.success.to_string()),
+                Cell::new(i.error.clone().unwrap_or_default()),
+            ]
+        }));
+        table.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_results_exit_code() {
+        let mut results = BatchResults::default();
+        results.push("A", Ok(()));
+        results.push("B", Err(anyhow::anyhow!("boom")));
+        assert_eq!(results.failed(), 1);
+
+        let err = results.finish(None, "delete").unwrap_err();
+        let err: &ExitCodeError = err.downcast_ref().unwrap();
+        assert_eq!(err.code, EXIT_CODE_PARTIAL_FAILURE);
+
+        let mut results = BatchResults::default();
+        results.push("A", Err(anyhow::anyhow!("boom")));
+        let err = results.finish(None, "delete").unwrap_err();
+        assert!(err.downcast_ref::<ExitCodeError>().is_none());
+
+        let mut results = BatchResults::default();
+        results.push("A", Ok(()));
+        assert!(results.finish(None, "delete").is_ok());
+    }
+}
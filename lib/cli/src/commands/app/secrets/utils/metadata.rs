@@ -0,0 +1,127 @@
+use super::warnings::Warnings;
+use std::{collections::BTreeMap, sync::Mutex};
+use time::OffsetDateTime;
+use wasmer_backend_api::types::{DateTime, Secret as BackendSecret};
+
+/// The metadata of a secret.
+///
+/// Depending on the server, a field may be missing or in an unexpected format;
+/// it is `None` in that case, and features depending on it must go through a
+/// [`MetadataCheck`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SecretMetadata {
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+impl From<&BackendSecret> for SecretMetadata {
+    fn from(secret: &BackendSecret) -> Self {
+        Self {
+            created_at: parse(&secret.created_at),
+            updated_at: parse(&secret.updated_at),
+        }
+    }
+}
+
+fn parse(value: &DateTime) -> Option<OffsetDateTime> {
+    OffsetDateTime::try_from(value.clone()).ok()
+}
+
+/// Decides what happens when a feature needs metadata that is not available
+/// for a secret: fail in strict mode, or fall back and warn once per feature.
+#[derive(Debug, Default)]
+pub(crate) struct MetadataCheck {
+    strict: bool,
+    /// The fallbacks taken so far, by feature, with the number of secrets.
+    fallbacks: Mutex<BTreeMap<&'static str, (&'static str, usize)>>,
+}
+
+impl MetadataCheck {
+    pub fn new(strict: bool) -> Self {
+        Self {
+            strict,
+            ..Default::default()
+        }
+    }
+
+    /// The update time of `secret`, as needed by `feature` (e.g. `--since`).
+    ///
+    /// If it is not available, this fails in strict mode, and otherwise returns
+    /// `None`: the caller must then apply the `fallback` it describes (e.g.
+    /// "the secrets were included anyway").
+    pub fn updated_at(
+        &self,
+        secret: &BackendSecret,
+        feature: &'static str,
+        fallback: &'static str,
+    ) -> anyhow::Result<Option<OffsetDateTime>> {
+        if let Some(updated_at) = SecretMetadata::from(secret).updated_at {
+            return Ok(Some(updated_at));
+        }
+        if self.strict {
+            anyhow::bail!(
+                "The update time of secret '{}' is not available, which `{feature}` needs.",
+                secret.name
+            );
+        }
+        self.fallbacks
+            .lock()
+            .unwrap()
+            .entry(feature)
+            .or_insert((fallback, 0))
+            .1 += 1;
+        Ok(None)
+    }
+
+    /// Add a warning for every feature that had to fall back.
+    pub fn report(&self, warnings: &mut Warnings) {
+        for (feature, (fallback, count)) in self.fallbacks.lock().unwrap().iter() {
+            warnings.push(
+                "missing_metadata",
+                format!(
+                    "The metadata `{feature}` needs is not available for {count} secret(s), so {fallback}. Pass `--strict` to fail instead."
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::backend_secret as secret;
+    use super::*;
+
+    #[test]
+    fn test_metadata_check() {
+        let check = MetadataCheck::new(false);
+        assert!(check
+            .updated_at(&secret("A", "2024-01-01T00:00:00Z"), "--since", "kept")
+            .unwrap()
+            .is_some());
+        assert!(check
+            .updated_at(&secret("B", ""), "--since", "kept")
+            .unwrap()
+            .is_none());
+        assert!(check
+            .updated_at(&secret("C", "yesterday"), "--since", "kept")
+            .unwrap()
+            .is_none());
+
+        let mut warnings = Warnings::default();
+        check.report(&mut warnings);
+        let [warning] = warnings.as_slice() else {
+            panic!("expected a single warning");
+        };
+        assert_eq!(warning.code, "missing_metadata");
+        assert!(
+            warning.message.contains("2 secret(s)"),
+            "{}",
+            warning.message
+        );
+
+        let strict = MetadataCheck::new(true);
+        assert!(strict
+            .updated_at(&secret("B", ""), "--since", "kept")
+            .is_err());
+    }
+}
@@ -1,14 +1,33 @@
+pub(crate) mod batch;
+pub(crate) mod metadata;
+pub(crate) mod output;
+pub(crate) mod policy;
+pub(crate) mod rename;
 pub(crate) mod render;
+pub(crate) mod signing;
+pub(crate) mod validation;
+pub(crate) mod value_filter;
+pub(crate) mod warnings;
+pub(crate) mod window;
 
+use anyhow::Context;
 use colored::Colorize;
 use std::{
+    collections::HashMap,
     env::current_dir,
     path::{Path, PathBuf},
     str::FromStr,
 };
-use wasmer_backend_api::{types::Secret as BackendSecret, WasmerClient};
+use wasmer_backend_api::{
+    secrets::SecretsApi,
+    types::{DeployApp, Secret as BackendSecret},
+    WasmerClient,
+};
 
-use crate::commands::app::util::{get_app_config_from_dir, prompt_app_ident, AppIdent};
+use crate::{
+    commands::app::util::{get_app_config_from_dir, prompt_app, AppIdent},
+    config::WasmerEnv,
+};
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub(super) struct Secret {
@@ -16,6 +35,49 @@ pub(super) struct Secret {
     pub value: String,
 }
 
+impl PartialEq for Secret {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && values_eq(&self.value, &other.value)
+    }
+}
+
+impl Eq for Secret {}
+
+/// Compare two secret values without the time taken depending on their content.
+///
+/// NOTE: this is best-effort. The length of the values is not hidden, and
+/// there is no guarantee that other code handling the strings (allocation,
+/// formatting, ...) runs in constant time.
+pub(crate) fn values_eq(a: &str, b: &str) -> bool {
+    use subtle::ConstantTimeEq;
+
+    a.as_bytes().ct_eq(b.as_bytes()).into()
+}
+
+/// The client used by the commands that change secrets.
+///
+/// It sends an idempotency key with every mutation, so that the backend can
+/// deduplicate the retries of a mutation (see [`WasmerClient::with_idempotency_keys`]).
+pub(super) fn mutation_client(env: &WasmerEnv) -> anyhow::Result<WasmerClient> {
+    Ok(env.client()?.with_idempotency_keys())
+}
+
+/// The SHA-256 checksum of a secret value, as lowercase hex.
+pub(crate) fn value_checksum(value: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
+
+/// Parse a SHA-256 checksum given on the command line (64 hex digits, in any case).
+pub(crate) fn parse_checksum(s: &str) -> Result<String, String> {
+    if s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(s.to_ascii_lowercase())
+    } else {
+        Err("expected a SHA-256 checksum (64 hexadecimal digits)".to_string())
+    }
+}
+
 pub(super) async fn read_secrets_from_file(path: &Path) -> anyhow::Result<Vec<Secret>> {
     let mut ret = vec![];
     for item in dotenvy::from_path_iter(path)? {
@@ -25,25 +87,135 @@ pub(super) async fn read_secrets_from_file(path: &Path) -> anyhow::Result<Vec<Se
     Ok(ret)
 }
 
+/// Parse a flat JSON object (`{"NAME": "value", ...}`) into a list of secrets.
+pub(super) fn read_secrets_from_json(reader: impl std::io::Read) -> anyhow::Result<Vec<Secret>> {
+    let obj: serde_json::Map<String, serde_json::Value> = serde_json::from_reader(reader)
+        .map_err(|e| anyhow::anyhow!("Could not parse secrets as a flat JSON object: {e}"))?;
+
+    let mut ret = vec![];
+    for (name, value) in obj {
+        match value {
+            serde_json::Value::String(value) => ret.push(Secret { name, value }),
+            other => anyhow::bail!(
+                "The value of secret '{}' must be a string, found '{other}'",
+                name.bold()
+            ),
+        }
+    }
+    Ok(ret)
+}
+
+/// Read the value of a secret from `reader`, e.g. stdin.
+///
+/// The value is kept byte for byte, except that a single trailing `\n` or
+/// `\r\n` is stripped if `trim` is set: most tools add one when piping a value.
+pub(super) fn read_value(mut reader: impl std::io::Read, trim: bool) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    reader
+        .read_to_end(&mut buf)
+        .context("Could not read the secret value")?;
+    let mut value = String::from_utf8(buf)
+        .map_err(|_| anyhow::anyhow!("The secret value is not valid UTF-8"))?;
+
+    if trim {
+        if value.ends_with("\r\n") {
+            value.truncate(value.len() - 2);
+        } else if value.ends_with('\n') {
+            value.truncate(value.len() - 1);
+        }
+    }
+
+    Ok(value)
+}
+
+/// Read secrets from a file: a flat JSON object if the file has a `.json`
+/// extension, a dotenv file otherwise.
+pub(super) async fn read_secrets_from_any_file(path: &Path) -> anyhow::Result<Vec<Secret>> {
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Could not open '{}'", path.display()))?;
+        read_secrets_from_json(std::io::BufReader::new(file))
+    } else {
+        read_secrets_from_file(path).await
+    }
+}
+
+/// Keep only the secrets that are missing from `baseline`, or whose value
+/// differs from the one in `baseline`.
+pub(super) fn changed_secrets(secrets: Vec<Secret>, baseline: &[Secret]) -> Vec<Secret> {
+    let baseline: HashMap<&str, &str> = baseline
+        .iter()
+        .map(|s| (s.name.as_str(), s.value.as_str()))
+        .collect();
+
+    secrets
+        .into_iter()
+        .filter(|s| {
+            baseline
+                .get(s.name.as_str())
+                .map_or(true, |value| !values_eq(value, &s.value))
+        })
+        .collect()
+}
+
 pub(super) async fn get_secret_by_name(
-    client: &WasmerClient,
+    client: &impl SecretsApi,
     app_id: &str,
     secret_name: &str,
 ) -> anyhow::Result<Option<BackendSecret>> {
-    wasmer_backend_api::query::get_app_secret_by_name(client, app_id, secret_name).await
+    client.get_app_secret_by_name(app_id, secret_name).await
+}
+
+/// Find the names that `input` may have been meant as, for "did you mean"
+/// suggestions.
+///
+/// Names equal to `input` ignoring case are returned alone if there are any.
+/// Otherwise, names that contain `input` or are within a small edit distance
+/// of it (both ignoring case) are returned, closest first.
+pub(crate) fn similar_names<'a>(input: &str, names: &'a [String]) -> Vec<&'a str> {
+    const MAX_DISTANCE: usize = 2;
+    const MAX_SUGGESTIONS: usize = 5;
+
+    let input = input.to_lowercase();
+    let same_case: Vec<&str> = names
+        .iter()
+        .filter(|n| n.to_lowercase() == input)
+        .map(String::as_str)
+        .collect();
+    if !same_case.is_empty() {
+        return same_case;
+    }
+
+    let mut candidates: Vec<(usize, &str)> = names
+        .iter()
+        .filter_map(|name| {
+            let lower = name.to_lowercase();
+            let distance = strsim::levenshtein(&input, &lower);
+            (distance <= MAX_DISTANCE || lower.contains(&input))
+                .then_some((distance, name.as_str()))
+        })
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect()
 }
+
 pub(crate) async fn get_secrets(
-    client: &WasmerClient,
+    client: &impl SecretsApi,
     app_id: &str,
 ) -> anyhow::Result<Vec<wasmer_backend_api::types::Secret>> {
-    wasmer_backend_api::query::get_all_app_secrets(client, app_id).await
+    client.get_all_app_secrets(app_id).await
 }
 
 pub(crate) async fn get_secret_value(
-    client: &WasmerClient,
+    client: &impl SecretsApi,
     secret: &wasmer_backend_api::types::Secret,
 ) -> anyhow::Result<String> {
-    wasmer_backend_api::query::get_app_secret_value_by_id(client, secret.id.clone().into_inner())
+    client
+        .get_app_secret_value_by_id(secret.id.inner())
         .await?
         .ok_or_else(|| {
             anyhow::anyhow!(
@@ -54,7 +226,7 @@ pub(crate) async fn get_secret_value(
 }
 
 pub(crate) async fn get_secret_value_by_name(
-    client: &WasmerClient,
+    client: &impl SecretsApi,
     app_id: &str,
     secret_name: &str,
 ) -> anyhow::Result<String> {
@@ -64,21 +236,87 @@ pub(crate) async fn get_secret_value_by_name(
     }
 }
 
+/// A secret whose value could not be revealed.
+#[derive(Debug, thiserror::Error)]
+#[error("could not reveal secret '{name}': {error:#}")]
+pub(crate) struct SecretError {
+    pub name: String,
+    pub error: anyhow::Error,
+}
+
+/// Reveal all the secrets of an app.
+///
+/// Failing to list the secrets is an error, but failing to fetch the value of
+/// a single secret is reported in its own entry, so that callers can decide
+/// whether to abort or to go on with the secrets that could be revealed.
+///
+/// Up to `concurrency` values are fetched at the same time, capped by
+/// `WASMER_MAX_CONCURRENCY`. Entries are returned in the order of the listing.
 pub(crate) async fn reveal_secrets(
-    client: &WasmerClient,
+    client: &impl SecretsApi,
     app_id: &str,
-) -> anyhow::Result<Vec<Secret>> {
-    let secrets = wasmer_backend_api::query::get_all_app_secrets(client, app_id).await?;
-    let mut ret = vec![];
-    for secret in secrets {
-        let name = secret.name.clone();
-        let value = get_secret_value(client, &secret).await?;
-        ret.push(Secret { name, value });
+    concurrency: usize,
+) -> anyhow::Result<Vec<Result<Secret, SecretError>>> {
+    reveal_secrets_where(client, app_id, concurrency, |_| Ok(true)).await
+}
+
+/// Like [`reveal_secrets`], but only reveals the secrets for which `keep`
+/// returns `true`. The values of the other secrets are never fetched.
+pub(crate) async fn reveal_secrets_where(
+    client: &impl SecretsApi,
+    app_id: &str,
+    concurrency: usize,
+    keep: impl Fn(&BackendSecret) -> anyhow::Result<bool>,
+) -> anyhow::Result<Vec<Result<Secret, SecretError>>> {
+    use futures::StreamExt;
+
+    let mut secrets = Vec::new();
+    for secret in client.get_all_app_secrets(app_id).await? {
+        if keep(&secret)? {
+            secrets.push(secret);
+        }
     }
+    let concurrency = wasmer_backend_api::concurrency::effective_concurrency(concurrency);
+    let ret = futures::stream::iter(secrets)
+        .map(|secret| async move {
+            let name = secret.name.clone();
+            match get_secret_value(client, &secret).await {
+                Ok(value) => Ok(Secret { name, value }),
+                Err(error) => Err(SecretError { name, error }),
+            }
+        })
+        .buffered(concurrency)
+        .collect()
+        .await;
 
     Ok(ret)
 }
 
+/// Check whether the secrets of the given app version can be revealed.
+pub(super) async fn check_versioned_secrets(
+    client: &WasmerClient,
+    app_id: &str,
+    version: &str,
+) -> anyhow::Result<()> {
+    let version = wasmer_backend_api::query::get_app_version_by_id(client, version.to_string())
+        .await
+        .with_context(|| format!("Could not find app version '{}'", version.bold()))?;
+
+    if version.app.as_ref().map(|a| a.id.inner()) != Some(app_id) {
+        anyhow::bail!(
+            "App version '{}' does not belong to app {app_id}",
+            version.id.inner().bold()
+        );
+    }
+
+    // The backend only stores the current value of each secret, so there is
+    // no snapshot to reveal for older versions.
+    anyhow::bail!(
+        "Revealing the secrets of a specific app version ('{}') is not supported for app {app_id}. Omit `--version` to reveal the current secrets.",
+        version.id.inner().bold()
+    )
+}
+
 /// Utility struct used just to implement [`CliRender`].
 #[derive(Debug, serde::Serialize)]
 pub(super) struct BackendSecretWrapper(pub BackendSecret);
@@ -89,6 +327,84 @@ impl From<BackendSecret> for BackendSecretWrapper {
     }
 }
 
+/// How the app a command operates on was determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ResolutionSource {
+    /// The `--app` flag.
+    Flag,
+    /// An app config file, either in `--app-dir` or in the current directory.
+    Config,
+    /// A guess based on the `origin` git remote (`--guess-from-git`).
+    Git,
+    /// The interactive prompt.
+    Prompt,
+}
+
+/// The outcome of resolving the app a command operates on.
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct AppResolution {
+    pub app_id: String,
+    /// The name of the app, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The owner of the app, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub source: ResolutionSource,
+    /// The path of the app config file, if the app was resolved from one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_path: Option<PathBuf>,
+}
+
+impl AppResolution {
+    fn new(app: DeployApp, source: ResolutionSource) -> Self {
+        Self {
+            app_id: app.id.into_inner(),
+            name: Some(app.name),
+            owner: Some(app.owner.global_name),
+            source,
+            config_path: None,
+        }
+    }
+
+    /// The name of the app, with its owner if known, e.g. `api (owner: acme)`.
+    ///
+    /// Falls back to the id of the app if its name is not known.
+    pub fn app_label(&self) -> String {
+        match (&self.name, &self.owner) {
+            (Some(name), Some(owner)) => format!("{} (owner: {owner})", name.bold()),
+            (Some(name), None) => name.bold().to_string(),
+            _ => self.app_id.bold().to_string(),
+        }
+    }
+
+    /// A human-readable description of where the app was resolved from.
+    pub fn source_description(&self) -> String {
+        match (self.source, &self.config_path) {
+            (ResolutionSource::Flag, _) => "the `--app` flag".to_string(),
+            (ResolutionSource::Config, Some(path)) => {
+                format!(
+                    "the app config at {}",
+                    format!("'{}'", path.display()).dimmed()
+                )
+            }
+            (ResolutionSource::Config, None) => "the app config".to_string(),
+            (ResolutionSource::Git, _) => "the git remote 'origin'".to_string(),
+            (ResolutionSource::Prompt, _) => "the interactive prompt".to_string(),
+        }
+    }
+
+    /// A human-readable description of how the app was resolved.
+    pub fn describe(&self) -> String {
+        format!(
+            "Resolved app {} from {}.",
+            self.app_id.bold(),
+            self.source_description()
+        )
+    }
+}
+
 /// A secrets-specific app to retrieve an app identifier.
 pub(super) async fn get_app_id(
     client: &WasmerClient,
@@ -96,10 +412,33 @@ pub(super) async fn get_app_id(
     app_dir_path: Option<&PathBuf>,
     quiet: bool,
     non_interactive: bool,
+    guess_from_git: bool,
 ) -> anyhow::Result<String> {
+    resolve_app(
+        client,
+        app,
+        app_dir_path,
+        quiet,
+        non_interactive,
+        guess_from_git,
+    )
+    .await
+    .map(|r| r.app_id)
+}
+
+/// Like [`get_app_id`], but also reports how the app was resolved.
+pub(super) async fn resolve_app(
+    client: &WasmerClient,
+    app: Option<&AppIdent>,
+    app_dir_path: Option<&PathBuf>,
+    quiet: bool,
+    non_interactive: bool,
+    guess_from_git: bool,
+) -> anyhow::Result<AppResolution> {
     if let Some(app_id) = app {
+        tracing::debug!(?app_id, "resolving app from the --app flag");
         let app = app_id.resolve(client).await?;
-        return Ok(app.id.into_inner());
+        return Ok(AppResolution::new(app, ResolutionSource::Flag));
     }
 
     let path = if let Some(path) = app_dir_path {
@@ -109,7 +448,8 @@ pub(super) async fn get_app_id(
     };
 
     if let Ok(r) = get_app_config_from_dir(&path) {
-        let (app, _) = r;
+        let (app, config_path) = r;
+        tracing::debug!(config_path=%config_path.display(), "resolving app from app config");
 
         let app_name = if let Some(owner) = &app.owner {
             format!("{owner}/{}", app.name)
@@ -146,7 +486,13 @@ pub(super) async fn get_app_id(
                     eprintln!("Managing secrets related to app {}.", app.name.bold());
                 }
             }
-            return Ok(id);
+            return Ok(AppResolution {
+                app_id: id,
+                name: Some(app.name.clone()),
+                owner: app.owner.clone(),
+                source: ResolutionSource::Config,
+                config_path: Some(config_path),
+            });
         }
     } else if let Some(path) = app_dir_path {
         anyhow::bail!(
@@ -155,11 +501,252 @@ pub(super) async fn get_app_id(
         )
     }
 
+    tracing::debug!("could not resolve app from flags or app config");
+
+    if guess_from_git {
+        if let Some(app) = guess_app_from_git(client, &path, quiet).await {
+            return Ok(AppResolution::new(app, ResolutionSource::Git));
+        }
+    }
+
     if non_interactive {
         anyhow::bail!("No app id given. Provide one using the `--app` flag.")
     } else {
-        let id = prompt_app_ident("Enter the name of the app")?;
-        let app = id.resolve(client).await?;
-        Ok(app.id.into_inner())
+        let app = prompt_app(client, "Enter the name of the app").await?;
+        Ok(AppResolution::new(app, ResolutionSource::Prompt))
+    }
+}
+
+/// Try to resolve the app from the name of the `origin` git remote of the given directory.
+///
+/// Both `<owner>/<repo>` and `<repo>` (in the namespace of the current user) are tried.
+async fn guess_app_from_git(client: &WasmerClient, dir: &Path, quiet: bool) -> Option<DeployApp> {
+    let output = std::process::Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let url = String::from_utf8(output.stdout).ok()?;
+    let (owner, repo) = parse_git_remote(url.trim())?;
+    tracing::debug!(%url, ?owner, %repo, "guessing app from the git remote");
+
+    let mut candidates = Vec::new();
+    if let Some(owner) = owner {
+        candidates.push(AppIdent::NamespacedName(owner, repo.clone()));
+    }
+    candidates.push(AppIdent::Name(repo));
+
+    for candidate in candidates {
+        if let Ok(app) = candidate.resolve(client).await {
+            if !quiet {
+                eprintln!(
+                    "Guessed app {} from the git remote 'origin'.",
+                    format!("{}/{}", app.owner.global_name, app.name).bold()
+                );
+            }
+            return Some(app);
+        }
+    }
+
+    if !quiet {
+        eprintln!(
+            "{}: could not find an app matching the git remote 'origin' ({url}).",
+            "Warning".bold().yellow(),
+            url = url.trim()
+        );
+    }
+    None
+}
+
+/// Extract the owner (if any) and repository name from a git remote URL.
+///
+/// Supports both URLs (`https://host/owner/repo.git`, `ssh://git@host/owner/repo`) and the
+/// scp-like syntax (`git@host:owner/repo.git`).
+fn parse_git_remote(url: &str) -> Option<(Option<String>, String)> {
+    let path = if let Some((_, rest)) = url.split_once("://") {
+        rest.split_once('/').map(|(_, path)| path)?
+    } else if let Some((_, path)) = url.split_once(':') {
+        path
+    } else {
+        url
+    };
+
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let mut segments = path.rsplit('/').filter(|s| !s.is_empty());
+
+    let repo = segments.next()?.to_string();
+    let owner = segments.next().map(|s| s.to_string());
+    Some((owner, repo))
+}
+
+/// A secret as listed by the API, last updated (and created) at `updated_at`, for tests.
+#[cfg(test)]
+fn backend_secret(name: &str, updated_at: &str) -> BackendSecret {
+    serde_json::from_value(serde_json::json!({
+        "id": format!("secret-{name}"),
+        "name": name,
+        "createdAt": updated_at,
+        "updatedAt": updated_at,
+    }))
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_secrets_from_json() {
+        let secrets = read_secrets_from_json(r#"{"B": "2", "A": "1"}"#.as_bytes()).unwrap();
+        let mut secrets: Vec<_> = secrets.into_iter().map(|s| (s.name, s.value)).collect();
+        secrets.sort();
+        assert_eq!(
+            secrets,
+            vec![
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "2".to_string())
+            ]
+        );
+
+        assert!(read_secrets_from_json(r#"{"A": 1}"#.as_bytes()).is_err());
+        assert!(read_secrets_from_json(r#"["A"]"#.as_bytes()).is_err());
+        assert!(read_secrets_from_json(r#"{"A": "1""#.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_similar_names() {
+        let names = ["DB_URL", "DB_USER", "API_KEY", "db_url"].map(String::from);
+        assert_eq!(similar_names("Db_Url", &names), vec!["DB_URL", "db_url"]);
+        assert_eq!(similar_names("API_KYE", &names), vec!["API_KEY"]);
+        assert_eq!(
+            similar_names("db", &names),
+            vec!["DB_URL", "db_url", "DB_USER"]
+        );
+        assert!(similar_names("REDIS", &names).is_empty());
+    }
+
+    #[test]
+    fn test_parse_git_remote() {
+        let expected = Some((Some("my-org".to_string()), "my-app".to_string()));
+        assert_eq!(
+            parse_git_remote("https://github.com/my-org/my-app.git"),
+            expected
+        );
+        assert_eq!(
+            parse_git_remote("https://github.com/my-org/my-app/"),
+            expected
+        );
+        assert_eq!(
+            parse_git_remote("git@github.com:my-org/my-app.git"),
+            expected
+        );
+        assert_eq!(
+            parse_git_remote("ssh://git@github.com:22/my-org/my-app"),
+            expected
+        );
+        assert_eq!(
+            parse_git_remote("/srv/git/my-app.git"),
+            Some((Some("git".to_string()), "my-app".to_string()))
+        );
+        assert_eq!(parse_git_remote("https://github.com/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_reveal_secrets_isolates_errors() {
+        use wasmer_backend_api::mock::MockSecretsApi;
+
+        let api = MockSecretsApi::new()
+            .with_secret("da_1", "A", "1")
+            .with_failing_secret("da_1", "B", "permission denied")
+            .with_secret("da_1", "C", "3");
+
+        let results = reveal_secrets(&api, "da_1", 2).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().value, "1");
+        let Err(err) = &results[1] else {
+            panic!("expected an error for secret B");
+        };
+        assert_eq!(err.name, "B");
+        assert!(err.to_string().contains("permission denied"));
+        assert_eq!(results[2].as_ref().unwrap().value, "3");
+
+        assert!(reveal_secrets(&api, "da_2", 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_secret_value_by_name() {
+        use wasmer_backend_api::mock::MockSecretsApi;
+
+        let api = MockSecretsApi::new().with_secret("da_1", "A", "1");
+
+        assert_eq!(
+            get_secret_value_by_name(&api, "da_1", "A").await.unwrap(),
+            "1"
+        );
+        assert!(get_secret_value_by_name(&api, "da_1", "B").await.is_err());
+        assert_eq!(
+            api.calls(),
+            vec![
+                "get_app_secret_by_name(da_1, A)",
+                "get_app_secret_value_by_id(da_1/secret-0)",
+                "get_app_secret_by_name(da_1, B)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_secrets() {
+        let secret = |name: &str, value: &str| Secret {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let baseline = [secret("A", "1"), secret("B", "2")];
+        let secrets = vec![secret("A", "1"), secret("B", "changed"), secret("C", "3")];
+
+        let names: Vec<_> = changed_secrets(secrets, &baseline)
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["B", "C"]);
+    }
+
+    #[test]
+    fn test_read_value() {
+        let read = |input: &[u8], trim| read_value(input, trim).unwrap();
+
+        assert_eq!(read(b"hunter2\n", true), "hunter2");
+        assert_eq!(read(b"hunter2\r\n", true), "hunter2");
+        assert_eq!(read(b"hunter2", true), "hunter2");
+        // Only a single trailing newline is stripped.
+        assert_eq!(read(b"hunter2\n\n", true), "hunter2\n");
+        assert_eq!(read(b"a\r\nb\r\n", true), "a\r\nb");
+        assert_eq!(read(b" padded \t", true), " padded \t");
+
+        assert_eq!(read(b"hunter2\r\n", false), "hunter2\r\n");
+        assert_eq!(read(b"", true), "");
+
+        assert!(read_value(&[0xff, 0xfe][..], true).is_err());
+    }
+
+    #[test]
+    fn test_values_eq() {
+        assert!(values_eq("hunter2", "hunter2"));
+        assert!(values_eq("", ""));
+        assert!(!values_eq("hunter2", "hunter3"));
+        assert!(!values_eq("hunter2", "hunter22"));
+    }
+
+    #[test]
+    fn test_value_checksum() {
+        let checksum = value_checksum("hunter2");
+        assert_eq!(
+            checksum,
+            "f52fbd32b2b3b86ff88ef6c490628285f482af15ddcb29541f94bcf526a3f6c7"
+        );
+        assert_eq!(parse_checksum(&checksum.to_uppercase()), Ok(checksum));
+        assert!(parse_checksum("f52fbd32").is_err());
+        assert!(parse_checksum(&"z".repeat(64)).is_err());
     }
 }
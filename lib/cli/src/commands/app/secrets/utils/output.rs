@@ -0,0 +1,256 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use flate2::write::GzEncoder;
+use tempfile::TempPath;
+
+/// A compression applied to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Compression {
+    Gzip,
+}
+
+/// Flags to write revealed secrets somewhere other than stdout.
+#[derive(clap::Parser, Debug, Clone, Default)]
+pub struct OutputOpts {
+    /// Write the output to the given file instead of stdout.
+    ///
+    /// The file is only replaced once the command succeeded, so an existing file is left as it
+    /// is if anything fails. On Unix, the file is only readable and writable by the current user.
+    #[clap(long, value_name = "PATH", conflicts_with = "out_fd")]
+    pub out: Option<PathBuf>,
+
+    /// Write the output to an inherited file descriptor instead of stdout, so that secrets
+    /// never touch the disk, e.g. `--out-fd 3 3> >(consumer)`.
+    ///
+    /// Only supported on Unix.
+    #[clap(long, value_name = "FD")]
+    pub out_fd: Option<i32>,
+
+    /// Compress the output as it is written.
+    ///
+    /// Defaults to gzip if the path given with `--out` ends in `.gz`.
+    #[clap(long, value_enum, value_name = "ALGORITHM")]
+    pub compress: Option<Compression>,
+}
+
+impl OutputOpts {
+    /// Whether the output goes to a terminal.
+    ///
+    /// Files and file descriptors given with `--out`/`--out-fd` never count as a terminal.
+    pub fn is_terminal(&self) -> bool {
+        use is_terminal::IsTerminal;
+
+        self.out.is_none() && self.out_fd.is_none() && std::io::stdout().is_terminal()
+    }
+
+    /// The compression to apply, given explicitly or inferred from the extension of `--out`.
+    pub fn compression(&self) -> Option<Compression> {
+        self.compress.or_else(|| {
+            self.out
+                .as_ref()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "gz"))
+                .map(|_| Compression::Gzip)
+        })
+    }
+
+    /// Open the selected output, defaulting to stdout.
+    pub fn open(&self) -> anyhow::Result<Output> {
+        let compression = self.compression();
+        if compression.is_some() && self.is_terminal() {
+            anyhow::bail!(
+                "refusing to write compressed output to a terminal; redirect stdout or use `--out`"
+            );
+        }
+
+        let (writer, pending) = self.open_writer()?;
+        let writer = match compression {
+            None => Writer::Plain(writer),
+            Some(Compression::Gzip) => {
+                Writer::Gzip(GzEncoder::new(writer, flate2::Compression::default()))
+            }
+        };
+        Ok(Output { writer, pending })
+    }
+
+    fn open_writer(&self) -> anyhow::Result<(Box<dyn Write + Send>, Option<PendingFile>)> {
+        if let Some(fd) = self.out_fd {
+            return Ok((open_fd(fd)?, None));
+        }
+
+        let Some(path) = &self.out else {
+            return Ok((Box::new(std::io::stdout()), None));
+        };
+
+        // Written next to the target, so that it can be renamed over it. Temporary
+        // files are only accessible by the current user.
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => std::path::Path::new("."),
+        };
+        let (file, temp) = tempfile::Builder::new()
+            .prefix(".wasmer-secrets-")
+            .tempfile_in(dir)
+            .map_err(|e| anyhow::anyhow!("could not create a file in '{}': {e}", dir.display()))?
+            .into_parts();
+        let pending = PendingFile {
+            temp,
+            target: path.clone(),
+        };
+        Ok((Box::new(file), Some(pending)))
+    }
+}
+
+/// An opened output, see [`OutputOpts::open`].
+///
+/// Compression happens while writing, so nothing is buffered beyond what the
+/// compressor needs. [`Output::finish`] must be called once everything was
+/// written, to complete a compressed stream and to move a `--out` file in
+/// place: if the output is dropped instead, the file is discarded.
+pub(crate) struct Output {
+    writer: Writer,
+    pending: Option<PendingFile>,
+}
+
+enum Writer {
+    Plain(Box<dyn Write + Send>),
+    Gzip(GzEncoder<Box<dyn Write + Send>>),
+}
+
+/// A temporary file, deleted when dropped unless it is moved over `target`.
+struct PendingFile {
+    temp: TempPath,
+    target: PathBuf,
+}
+
+impl Output {
+    /// Complete the output and flush it.
+    pub fn finish(self) -> io::Result<()> {
+        match self.writer {
+            Writer::Plain(mut writer) => writer.flush()?,
+            Writer::Gzip(encoder) => encoder.finish()?.flush()?,
+        }
+        if let Some(pending) = self.pending {
+            pending.temp.persist(&pending.target).map_err(|e| e.error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.writer {
+            Writer::Plain(writer) => writer.write(buf),
+            Writer::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.writer {
+            Writer::Plain(writer) => writer.flush(),
+            Writer::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Open a duplicate of the inherited file descriptor `fd`.
+///
+/// The descriptor itself is never taken over, so it is not closed by us (and can
+/// not be closed twice): only the duplicate is closed once the output is dropped,
+/// and the original one is closed when the process exits.
+#[cfg(unix)]
+fn open_fd(fd: i32) -> anyhow::Result<Box<dyn Write + Send>> {
+    use std::os::fd::BorrowedFd;
+
+    if (0..=2).contains(&fd) {
+        anyhow::bail!(
+            "--out-fd cannot be a standard stream (0, 1 or 2); omit it to write to stdout"
+        );
+    }
+    // SAFETY: F_GETFD has no side effects, and only tells whether `fd` is open.
+    if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        anyhow::bail!("--out-fd {fd} is not an open file descriptor");
+    }
+
+    // SAFETY: `fd` was checked to be open, and the borrow does not outlive this
+    // function.
+    let owned = unsafe { BorrowedFd::borrow_raw(fd) }
+        .try_clone_to_owned()
+        .map_err(|e| anyhow::anyhow!("could not duplicate file descriptor {fd}: {e}"))?;
+    Ok(Box::new(std::fs::File::from(owned)))
+}
+
+#[cfg(not(unix))]
+fn open_fd(_fd: i32) -> anyhow::Result<Box<dyn Write + Send>> {
+    anyhow::bail!("--out-fd is only supported on Unix")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_compression_inferred_from_extension() {
+        let opts = |out: &str, compress| OutputOpts {
+            out: Some(PathBuf::from(out)),
+            out_fd: None,
+            compress,
+        };
+        assert_eq!(
+            opts("secrets.env.gz", None).compression(),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(opts("secrets.env", None).compression(), None);
+        assert_eq!(
+            opts("secrets.env", Some(Compression::Gzip)).compression(),
+            Some(Compression::Gzip)
+        );
+    }
+
+    #[test]
+    fn test_out_file_is_replaced_on_finish_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.env");
+        std::fs::write(&path, "OLD=1\n").unwrap();
+        let opts = OutputOpts {
+            out: Some(path.clone()),
+            ..Default::default()
+        };
+
+        // A failed command drops the output without finishing it.
+        let mut out = opts.open().unwrap();
+        writeln!(out, "NEW=1").unwrap();
+        drop(out);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "OLD=1\n");
+
+        let mut out = opts.open().unwrap();
+        writeln!(out, "NEW=1").unwrap();
+        out.finish().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "NEW=1\n");
+        // No temporary file is left behind.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_gzip_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.env.gz");
+        let opts = OutputOpts {
+            out: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let mut out = opts.open().unwrap();
+        writeln!(out, "A=1").unwrap();
+        out.finish().unwrap();
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(path).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "A=1\n");
+    }
+}
@@ -0,0 +1,103 @@
+use super::{values_eq, warnings::Warnings};
+use crate::error::ExitCodeError;
+
+/// The exit code used when a command is refused because of how it was invoked.
+pub(crate) const EXIT_CODE_USAGE: i32 = 2;
+
+/// The environment variable with the comma-separated confirmation tokens that
+/// allow revealing all secrets non-interactively.
+///
+/// The backend has no per-app reveal policy, so this local allowlist is what
+/// gates bulk reveals: when it is set, `reveal --all` in non-interactive mode
+/// requires a matching `--confirm-token`.
+pub(crate) const REVEAL_TOKENS_ENV: &str = "WASMER_SECRETS_REVEAL_TOKENS";
+
+/// Enforce the [`REVEAL_TOKENS_ENV`] allowlist for a command about to reveal
+/// all secrets of an app non-interactively, e.g. `reveal --all` or `export`.
+///
+/// Must be called before anything is fetched. A `token` given while no
+/// allowlist is set is reported in `warnings`.
+pub(crate) fn check_bulk_reveal(
+    token: Option<&str>,
+    warnings: &mut Warnings,
+) -> Result<(), ExitCodeError> {
+    let allowed = std::env::var(REVEAL_TOKENS_ENV).ok();
+    check_bulk_reveal_against(allowed.as_deref(), token, warnings)
+}
+
+/// [`check_bulk_reveal`], with the allowlist given in `allowed` instead of
+/// read from [`REVEAL_TOKENS_ENV`].
+pub(crate) fn check_bulk_reveal_against(
+    allowed: Option<&str>,
+    token: Option<&str>,
+    warnings: &mut Warnings,
+) -> Result<(), ExitCodeError> {
+    if allowed.is_none() && token.is_some() {
+        warnings.push(
+            "ignored_flag",
+            format!("`--confirm-token` has no effect, as {REVEAL_TOKENS_ENV} is not set."),
+        );
+    }
+    check_confirm_token(allowed, token)
+}
+
+/// Check the `--confirm-token` of a non-interactive bulk reveal against the
+/// allowlist in `allowed` (the value of [`REVEAL_TOKENS_ENV`]).
+///
+/// Without an allowlist there is nothing to check.
+pub(crate) fn check_confirm_token(
+    allowed: Option<&str>,
+    token: Option<&str>,
+) -> Result<(), ExitCodeError> {
+    let Some(allowed) = allowed.filter(|a| !a.trim().is_empty()) else {
+        return Ok(());
+    };
+    let Some(token) = token else {
+        return Err(ExitCodeError::new(
+            EXIT_CODE_USAGE,
+            format!(
+                "Revealing all secrets non-interactively requires a confirmation token ({REVEAL_TOKENS_ENV} is set). Pass one with `--confirm-token`."
+            ),
+        ));
+    };
+
+    // Every candidate is compared, so that the time taken does not tell which
+    // one matched.
+    let matched = allowed
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .fold(false, |matched, t| values_eq(t, token) | matched);
+    if matched {
+        Ok(())
+    } else {
+        Err(ExitCodeError::new(
+            EXIT_CODE_USAGE,
+            format!("The confirmation token does not match any token of {REVEAL_TOKENS_ENV}."),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_confirm_token() {
+        assert!(check_confirm_token(None, None).is_ok());
+        assert!(check_confirm_token(Some(""), None).is_ok());
+
+        let allowed = Some("alpha, beta");
+        assert!(check_confirm_token(allowed, Some("beta")).is_ok());
+        assert_eq!(
+            check_confirm_token(allowed, Some("gamma"))
+                .unwrap_err()
+                .code,
+            EXIT_CODE_USAGE
+        );
+        assert_eq!(
+            check_confirm_token(allowed, None).unwrap_err().code,
+            EXIT_CODE_USAGE
+        );
+    }
+}
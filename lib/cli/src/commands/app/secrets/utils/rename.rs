@@ -0,0 +1,167 @@
+use super::{
+    validation::{validate_secret_name, ValidationErrors},
+    Secret,
+};
+use colored::Colorize;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Flags to rewrite the names of secrets while importing them.
+#[derive(clap::Parser, Debug, Clone, Default)]
+pub struct RenameOpts {
+    /// Rewrite secret names with a sed-style substitution, e.g. `s/^STAGING_//` or
+    /// `s/^/PROD_/`. Can be repeated; rules are applied in order.
+    ///
+    /// The pattern is a regular expression; capture groups can be referenced in the
+    /// replacement as `$1`, `$name`, ...
+    #[clap(long = "rename", value_name = "s/PATTERN/REPLACEMENT/", value_parser = RenameRule::parse)]
+    pub rules: Vec<RenameRule>,
+}
+
+/// A single `s/PATTERN/REPLACEMENT/` rewrite rule.
+#[derive(Debug, Clone)]
+pub struct RenameRule {
+    pattern: Regex,
+    replacement: String,
+}
+
+impl RenameRule {
+    /// Parse a rule in `s/PATTERN/REPLACEMENT/` syntax.
+    ///
+    /// Like in sed, any character following the `s` is used as the delimiter.
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        let Some(rest) = s.strip_prefix('s') else {
+            anyhow::bail!("invalid rename rule '{s}': expected the form s/PATTERN/REPLACEMENT/");
+        };
+        let Some(delim) = rest.chars().next() else {
+            anyhow::bail!("invalid rename rule '{s}': expected the form s/PATTERN/REPLACEMENT/");
+        };
+
+        let parts: Vec<&str> = rest[delim.len_utf8()..].split(delim).collect();
+        let [pattern, replacement, ""] = parts.as_slice() else {
+            anyhow::bail!("invalid rename rule '{s}': expected the form s{delim}PATTERN{delim}REPLACEMENT{delim}");
+        };
+
+        let pattern = Regex::new(pattern)
+            .map_err(|e| anyhow::anyhow!("invalid pattern in rename rule '{s}': {e}"))?;
+
+        Ok(Self {
+            pattern,
+            replacement: replacement.to_string(),
+        })
+    }
+
+    fn apply(&self, name: &str) -> String {
+        self.pattern
+            .replace(name, self.replacement.as_str())
+            .into_owned()
+    }
+}
+
+impl RenameOpts {
+    /// Apply all rules, in order, to the given name.
+    pub fn rename(&self, name: &str) -> String {
+        self.rules
+            .iter()
+            .fold(name.to_string(), |name, rule| rule.apply(&name))
+    }
+
+    /// Rename the given secrets, printing a before → after preview of every changed name.
+    ///
+    /// Fails if two secrets end up with the same name, or with [`ValidationErrors`] if rewritten
+    /// names are not valid secret names.
+    pub(in crate::commands::app::secrets) fn apply(
+        &self,
+        secrets: Vec<Secret>,
+        quiet: bool,
+    ) -> anyhow::Result<Vec<Secret>> {
+        if self.rules.is_empty() {
+            return Ok(secrets);
+        }
+
+        let mut sources = HashMap::<String, String>::new();
+        let mut renamed = Vec::with_capacity(secrets.len());
+        let mut errors = ValidationErrors::default();
+
+        for Secret { name, value } in secrets {
+            let new_name = self.rename(&name);
+
+            if new_name != name {
+                if let Err(mut err) = validate_secret_name(&new_name) {
+                    err.reason = format!("renamed from '{name}': {}", err.reason);
+                    errors.0.push(err);
+                }
+                if !quiet {
+                    eprintln!("{} → {}", name, new_name.bold());
+                }
+            }
+
+            if let Some(other) = sources.get(&new_name) {
+                if *other != name {
+                    anyhow::bail!(
+                        "Secrets '{other}' and '{name}' would both be renamed to '{new_name}'"
+                    );
+                }
+            }
+            sources.insert(new_name.clone(), name);
+
+            renamed.push(Secret {
+                name: new_name,
+                value,
+            });
+        }
+
+        errors.into_result()?;
+        Ok(renamed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(rules: &[&str]) -> RenameOpts {
+        RenameOpts {
+            rules: rules
+                .iter()
+                .map(|r| RenameRule::parse(r).unwrap())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_rename_rules() {
+        assert_eq!(opts(&["s/^STAGING_//"]).rename("STAGING_DB_URL"), "DB_URL");
+        assert_eq!(opts(&["s/^/PROD_/"]).rename("DB_URL"), "PROD_DB_URL");
+        assert_eq!(
+            opts(&["s|^STAGING_(.*)$|${1}_PROD|", "s/DB/DATABASE/"]).rename("STAGING_DB_URL"),
+            "DATABASE_URL_PROD"
+        );
+        assert_eq!(opts(&["s/^OLD_/NEW_/"]).rename("OTHER"), "OTHER");
+
+        assert!(RenameRule::parse("OLD_/NEW_").is_err());
+        assert!(RenameRule::parse("s/OLD_/NEW_").is_err());
+        assert!(RenameRule::parse("s/(/X/").is_err());
+    }
+
+    #[test]
+    fn test_rename_collisions() {
+        let secrets = vec![
+            Secret {
+                name: "STAGING_A".to_string(),
+                value: "1".to_string(),
+            },
+            Secret {
+                name: "A".to_string(),
+                value: "2".to_string(),
+            },
+        ];
+        assert!(opts(&["s/^STAGING_//"]).apply(secrets, true).is_err());
+
+        let secrets = vec![Secret {
+            name: "A".to_string(),
+            value: "1".to_string(),
+        }];
+        assert!(opts(&["s/A/1A/"]).apply(secrets, true).is_err());
+    }
+}
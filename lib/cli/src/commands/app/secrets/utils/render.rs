@@ -1,9 +1,12 @@
-use super::{BackendSecretWrapper, Secret};
-use crate::utils::render::CliRender;
+use super::{
+    metadata::SecretMetadata, warnings::Warning, AppResolution, BackendSecretWrapper, Secret,
+    SecretError,
+};
+use crate::utils::render::{CliRender, ListFormat};
 use colored::Colorize;
 use comfy_table::{Cell, Table};
 use time::OffsetDateTime;
-use wasmer_backend_api::types::{DateTime, Secret as BackendSecret};
+use wasmer_backend_api::types::{DeploySecretLogActionChoices, Secret as BackendSecret, SecretLog};
 
 impl CliRender for Secret {
     fn render_item_table(&self) -> String {
@@ -49,17 +52,11 @@ impl CliRender for Secret {
 impl CliRender for BackendSecretWrapper {
     fn render_item_table(&self) -> String {
         let mut table = Table::new();
-        let BackendSecret {
-            name, updated_at, ..
-        }: &BackendSecret = &self.0;
-        let last_updated = last_updated_to_human(updated_at.clone())
-            .unwrap()
-            .to_string();
         table.add_rows([
-            vec!["Name".to_string(), name.to_string()],
+            vec!["Name".to_string(), self.0.name.to_string()],
             vec![
                 "Last updated".to_string(),
-                format!("{last_updated} ago").dimmed().to_string(),
+                describe_last_updated(&self.0).dimmed().to_string(),
             ],
         ]);
         table.to_string()
@@ -78,26 +75,466 @@ impl CliRender for BackendSecretWrapper {
             Cell::new("Last updated".to_string()).add_attribute(comfy_table::Attribute::Bold),
         ]);
         table.add_rows(items.iter().map(|s| {
-            let last_updated = last_updated_to_human(s.0.updated_at.clone())
-                .unwrap()
-                .to_string();
             vec![
                 Cell::new(s.0.name.clone()),
-                Cell::new(format!("{last_updated} ago").dimmed().to_string()),
+                Cell::new(describe_last_updated(&s.0).dimmed().to_string()),
             ]
         }));
         table.to_string()
     }
 }
 
-fn last_updated_to_human(last_update: DateTime) -> anyhow::Result<humantime::Duration> {
-    let last_update: OffsetDateTime = last_update.try_into()?;
+/// An event of the secret log of an app.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub(crate) struct SecretLogEntry {
+    pub secret: String,
+    /// One of `access`, `create`, `modification` and `delete`.
+    pub action: &'static str,
+    /// When the event happened, as an RFC 3339 timestamp.
+    pub time: String,
+}
+
+impl From<SecretLog> for SecretLogEntry {
+    fn from(log: SecretLog) -> Self {
+        let action = match log.action {
+            DeploySecretLogActionChoices::Access => "access",
+            DeploySecretLogActionChoices::Create => "create",
+            DeploySecretLogActionChoices::Modification => "modification",
+            DeploySecretLogActionChoices::Delete => "delete",
+        };
+        Self {
+            secret: log.secret_name,
+            action,
+            time: log.created_at.0,
+        }
+    }
+}
+
+impl SecretLogEntry {
+    /// Render `items` as the rows of a table, with or without the header: with
+    /// `--follow`, the header is only printed above the first page.
+    pub fn render_rows(items: &[Self], header: bool) -> String {
+        if items.is_empty() {
+            return String::new();
+        }
+        let mut table = Table::new();
+        table.load_preset(comfy_table::presets::NOTHING);
+        table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+
+        if header {
+            table.set_header(vec![
+                Cell::new("Time".to_string()).add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Secret".to_string()).add_attribute(comfy_table::Attribute::Bold),
+                Cell::new("Action".to_string()).add_attribute(comfy_table::Attribute::Bold),
+            ]);
+        }
+        table.add_rows(items.iter().map(|e| {
+            vec![
+                Cell::new(e.time.dimmed().to_string()),
+                Cell::new(e.secret.clone()),
+                Cell::new(e.action),
+            ]
+        }));
+        table.to_string()
+    }
+}
+
+impl CliRender for SecretLogEntry {
+    fn render_item_table(&self) -> String {
+        let mut table = Table::new();
+        table.add_rows([
+            vec!["Time".to_string(), self.time.clone()],
+            vec!["Secret".to_string(), self.secret.clone()],
+            vec!["Action".to_string(), self.action.to_string()],
+        ]);
+        table.to_string()
+    }
+
+    fn render_list_table(items: &[Self]) -> String {
+        Self::render_rows(items, true)
+    }
+}
+
+/// A secret listed with `--presence`, without its value.
+///
+/// Whether the value is empty cannot be told without fetching it, so only the
+/// name is reported.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct SecretPresence {
+    pub name: String,
+}
+
+impl From<&BackendSecret> for SecretPresence {
+    fn from(secret: &BackendSecret) -> Self {
+        Self {
+            name: secret.name.clone(),
+        }
+    }
+}
+
+impl CliRender for SecretPresence {
+    fn render_item_table(&self) -> String {
+        let mut table = Table::new();
+        table.add_rows([vec!["Name".to_string(), self.name.clone()]]);
+        table.to_string()
+    }
+
+    fn render_list_table(items: &[Self]) -> String {
+        if items.is_empty() {
+            return String::new();
+        }
+        let mut table = Table::new();
+        table.load_preset(comfy_table::presets::NOTHING);
+        table.set_content_arrangement(comfy_table::ContentArrangement::Dynamic);
+
+        table.set_header(vec![
+            Cell::new("Name".to_string()).add_attribute(comfy_table::Attribute::Bold)
+        ]);
+        table.add_rows(items.iter().map(|s| vec![Cell::new(s.name.clone())]));
+        table.to_string()
+    }
+}
+
+fn last_updated_to_human(last_update: OffsetDateTime) -> anyhow::Result<humantime::Duration> {
     let elapsed: std::time::Duration = (OffsetDateTime::now_utc() - last_update).try_into()?;
     Ok(humantime::Duration::from(std::time::Duration::from_secs(
         elapsed.as_secs(),
     )))
 }
 
+/// How long ago the secret was last updated, or "unknown" if the update time
+/// is not available.
+fn describe_last_updated(secret: &BackendSecret) -> String {
+    SecretMetadata::from(secret)
+        .updated_at
+        .and_then(|updated_at| last_updated_to_human(updated_at).ok())
+        .map_or_else(|| "unknown".to_string(), |elapsed| format!("{elapsed} ago"))
+}
+
+/// Flags controlling how secret values are masked when displayed.
+#[derive(clap::Parser, Debug)]
+pub struct MaskOpts {
+    /// Mask secret values instead of revealing them.
+    #[clap(long)]
+    pub mask: bool,
+
+    /// The character used to mask secret values.
+    #[clap(long, default_value_t = MaskPolicy::DEFAULT_MASK_CHAR, requires = "mask")]
+    pub mask_char: char,
+
+    /// Mask every value with the same number of characters, so that the masked form does not
+    /// reveal the length of the value.
+    #[clap(long, requires = "mask")]
+    pub mask_fixed: bool,
+}
+
+impl MaskOpts {
+    /// The masking policy to apply, if masking was requested.
+    pub fn policy(&self) -> Option<MaskPolicy> {
+        self.mask.then_some(MaskPolicy {
+            mask_char: self.mask_char,
+            fixed_width: self.mask_fixed,
+        })
+    }
+}
+
+/// How secret values are masked when displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MaskPolicy {
+    pub mask_char: char,
+    /// Use [`Self::FIXED_WIDTH`] mask characters instead of one per character of the value.
+    pub fixed_width: bool,
+}
+
+impl MaskPolicy {
+    pub const DEFAULT_MASK_CHAR: char = '*';
+    pub const FIXED_WIDTH: usize = 8;
+
+    pub fn mask(&self, value: &str) -> String {
+        let width = if self.fixed_width {
+            Self::FIXED_WIDTH
+        } else {
+            value.chars().count()
+        };
+        std::iter::repeat(self.mask_char).take(width).collect()
+    }
+}
+
+impl Default for MaskPolicy {
+    fn default() -> Self {
+        Self {
+            mask_char: Self::DEFAULT_MASK_CHAR,
+            fixed_width: false,
+        }
+    }
+}
+
+/// A version of the structure of machine-readable (json, yaml) output.
+///
+/// Once published, the structure of a version never changes: new fields and
+/// wrappers go into a new version, and older versions are produced by
+/// converting the latest structure back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum OutputVersion {
+    V1,
+}
+
+impl OutputVersion {
+    pub const LATEST: Self = Self::V1;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "1" => Ok(Self::V1),
+            other => Err(format!(
+                "unsupported output version '{other}' (supported: 1)"
+            )),
+        }
+    }
+
+    /// Convert a value in the structure of [`Self::LATEST`] to this version.
+    fn convert(self, value: serde_json::Value) -> serde_json::Value {
+        match self {
+            Self::V1 => value,
+        }
+    }
+}
+
+/// Flag to pin the structure of machine-readable output.
+#[derive(clap::Parser, Debug, Clone, Default)]
+pub struct OutputVersionOpts {
+    /// Pin the structure of json and yaml output to the given version, so that scripts are not
+    /// affected by later changes. Defaults to the latest version (currently 1).
+    #[clap(long, value_name = "N", value_parser = OutputVersion::parse)]
+    pub output_version: Option<OutputVersion>,
+}
+
+impl OutputVersionOpts {
+    pub fn version(&self) -> OutputVersion {
+        self.output_version.unwrap_or(OutputVersion::LATEST)
+    }
+}
+
+/// Whether `format` is a structured format (json, yaml), as opposed to a table.
+pub(crate) fn is_structured(format: ListFormat) -> bool {
+    matches!(format, ListFormat::Json | ListFormat::Yaml)
+}
+
+/// Render `value` under `key`, next to a `resolution` object reporting how the
+/// app was resolved.
+///
+/// Fails for formats that are not structured (see [`is_structured`]).
+pub(crate) fn render_with_resolution<T: serde::Serialize>(
+    format: ListFormat,
+    version: OutputVersion,
+    key: &str,
+    value: &T,
+    resolution: &AppResolution,
+) -> anyhow::Result<String> {
+    let mut out = serde_json::Map::new();
+    out.insert("resolution".to_string(), serde_json::to_value(resolution)?);
+    out.insert(key.to_string(), serde_json::to_value(value)?);
+
+    render_versioned(format, version, &out)
+}
+
+/// Render `value` in the given structured format (json, yaml), in the
+/// structure of the given output version.
+///
+/// Fails for formats that are not structured (see [`is_structured`]).
+pub(crate) fn render_versioned<T: serde::Serialize>(
+    format: ListFormat,
+    version: OutputVersion,
+    value: &T,
+) -> anyhow::Result<String> {
+    let value = version.convert(serde_json::to_value(value)?);
+    render_structured(format, &value)
+}
+
+/// Render `value` as a single line of JSON, in the structure of the given
+/// output version, for newline-delimited output.
+pub(crate) fn render_json_line<T: serde::Serialize>(
+    version: OutputVersion,
+    value: &T,
+) -> anyhow::Result<String> {
+    let value = version.convert(serde_json::to_value(value)?);
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// Render `value` in the given structured format (json, yaml).
+///
+/// Fails for formats that are not structured (see [`is_structured`]).
+pub(crate) fn render_structured<T: serde::Serialize>(
+    format: ListFormat,
+    value: &T,
+) -> anyhow::Result<String> {
+    match format {
+        ListFormat::Json => Ok(serde_json::to_string_pretty(value)?),
+        ListFormat::Yaml => Ok(serde_yaml::to_string(value)?),
+        ListFormat::Table | ListFormat::ItemTable => {
+            anyhow::bail!("cannot render a table as a structured format")
+        }
+    }
+}
+
+/// A self-describing wrapper of a list of secrets, with metadata about where and
+/// when they were fetched (`--envelope`).
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct Envelope<'a, T> {
+    pub app: EnvelopeApp<'a>,
+    /// When the secrets were fetched, in RFC 3339 format.
+    pub fetched_at: String,
+    pub count: usize,
+    /// How the app was resolved, with `--explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<&'a AppResolution>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub warnings: &'a [Warning],
+    pub secrets: &'a [T],
+}
+
+/// The app of an [`Envelope`].
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct EnvelopeApp<'a> {
+    pub id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<&'a str>,
+}
+
+impl<'a, T> Envelope<'a, T> {
+    pub fn new(
+        resolution: &'a AppResolution,
+        fetched_at: OffsetDateTime,
+        secrets: &'a [T],
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            app: EnvelopeApp {
+                id: &resolution.app_id,
+                name: resolution.name.as_deref(),
+                owner: resolution.owner.as_deref(),
+            },
+            fetched_at: fetched_at.format(&time::format_description::well_known::Rfc3339)?,
+            count: secrets.len(),
+            resolution: None,
+            warnings: &[],
+            secrets,
+        })
+    }
+}
+
+/// An entry of the structured output of `reveal --all`: either the value of a
+/// secret, or the error that prevented revealing it.
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct RevealedSecret<'a> {
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl<'a> From<&'a Secret> for RevealedSecret<'a> {
+    fn from(secret: &'a Secret) -> Self {
+        Self {
+            name: &secret.name,
+            value: Some(&secret.value),
+            error: None,
+        }
+    }
+}
+
+impl<'a> From<&'a SecretError> for RevealedSecret<'a> {
+    fn from(err: &'a SecretError) -> Self {
+        Self {
+            name: &err.name,
+            value: None,
+            error: Some(format!("{:#}", err.error)),
+        }
+    }
+}
+
+/// Truncate `value` to at most `max_bytes` bytes (on a character boundary),
+/// followed by a marker with the length of the full value.
+pub(crate) fn truncate_value(value: &str, max_bytes: usize) -> String {
+    if value.len() <= max_bytes {
+        return value.to_string();
+    }
+
+    let mut end = max_bytes;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…(truncated, {} bytes)", &value[..end], value.len())
+}
+
+/// Render a `.env.example` template: a `NAME=` line for each of the given names,
+/// sorted and deduplicated.
+pub(crate) fn render_example<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    let names: std::collections::BTreeSet<&str> = names.into_iter().collect();
+    let mut out = String::new();
+    for name in names {
+        out.push_str(name);
+        out.push_str("=\n");
+    }
+    out
+}
+
+/// Render secrets as dotenv lines (`NAME="value"`).
+pub(crate) fn render_dotenv(secrets: &[Secret]) -> String {
+    let mut out = String::new();
+    for secret in secrets {
+        out.push_str(&format!(
+            "{}=\"{}\"\n",
+            secret.name,
+            sanitize_value(&secret.value)
+        ));
+    }
+    out
+}
+
+/// Sort items by name.
+///
+/// By default names are sorted by byte (i.e. code point) order, which does not
+/// depend on the locale, so that the output is the same on every machine. With
+/// `human` set (`--locale-sort`), names are compared case-insensitively first,
+/// which reads more naturally in interactive use.
+pub(crate) fn sort_by_name<T>(items: &mut [T], name: impl Fn(&T) -> &str, human: bool) {
+    if human {
+        items.sort_by(|a, b| {
+            let (a, b) = (name(a), name(b));
+            a.to_lowercase()
+                .cmp(&b.to_lowercase())
+                .then_with(|| a.cmp(b))
+        });
+    } else {
+        items.sort_by(|a, b| name(a).cmp(name(b)));
+    }
+}
+
+/// Prepare a list of secrets for byte-stable rendering.
+///
+/// Secrets are deduplicated by name (the last occurrence wins) and sorted by
+/// name, in byte order (see [`sort_by_name`]).
+pub(crate) fn canonicalize(secrets: Vec<Secret>) -> Vec<Secret> {
+    let secrets: std::collections::BTreeMap<String, String> =
+        secrets.into_iter().map(|s| (s.name, s.value)).collect();
+    secrets
+        .into_iter()
+        .map(|(name, value)| Secret { name, value })
+        .collect()
+}
+
+/// Normalize line endings to `\n` and make sure non-empty output ends with
+/// exactly one newline.
+pub(crate) fn normalize_line_endings(out: &str) -> String {
+    let mut out = out.replace("\r\n", "\n").trim_end_matches('\n').to_string();
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
 pub(crate) fn sanitize_value(value: &str) -> String {
     value
         .chars()
@@ -112,3 +549,154 @@ pub(crate) fn sanitize_value(value: &str) -> String {
         .collect::<Vec<String>>()
         .join("")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_dotenv() {
+        let secrets = vec![
+            Secret {
+                name: "B".to_string(),
+                value: "old".to_string(),
+            },
+            Secret {
+                name: "A".to_string(),
+                value: "a\r\nb".to_string(),
+            },
+            Secret {
+                name: "B".to_string(),
+                value: "new".to_string(),
+            },
+        ];
+
+        let out = normalize_line_endings(&render_dotenv(&canonicalize(secrets)));
+        assert_eq!(out, "A=\"a\\r\\nb\"\nB=\"new\"\n");
+        assert_eq!(normalize_line_endings("x\r\ny\r\n\r\n"), "x\ny\n");
+        assert_eq!(normalize_line_endings(""), "");
+    }
+
+    #[test]
+    fn test_sort_by_name() {
+        let mut names = vec!["b", "B", "_a", "A", "a"];
+        sort_by_name(&mut names, |n| n, false);
+        assert_eq!(names, vec!["A", "B", "_a", "a", "b"]);
+
+        sort_by_name(&mut names, |n| n, true);
+        assert_eq!(names, vec!["_a", "A", "a", "B", "b"]);
+    }
+
+    #[test]
+    fn test_truncate_value() {
+        assert_eq!(truncate_value("short", 10), "short");
+        assert_eq!(
+            truncate_value("0123456789", 4),
+            "0123…(truncated, 10 bytes)"
+        );
+        // Never split a multi-byte character.
+        assert_eq!(truncate_value("aé", 2), "a…(truncated, 3 bytes)");
+    }
+
+    #[test]
+    fn test_mask_policy() {
+        assert_eq!(MaskPolicy::default().mask("hunter2"), "*******");
+        assert_eq!(MaskPolicy::default().mask("héllo"), "*****");
+
+        let policy = MaskPolicy {
+            mask_char: '•',
+            fixed_width: true,
+        };
+        assert_eq!(policy.mask("a"), "••••••••");
+        assert_eq!(policy.mask("a much longer secret value"), "••••••••");
+    }
+
+    #[test]
+    fn test_secret_presence() {
+        let secret = super::super::backend_secret("SET", "2024-01-01T00:00:00Z");
+
+        assert_eq!(
+            serde_json::to_value([SecretPresence::from(&secret)]).unwrap(),
+            serde_json::json!([{"name": "SET"}])
+        );
+    }
+
+    #[test]
+    fn test_envelope() {
+        let resolution = AppResolution {
+            app_id: "da_1".to_string(),
+            name: Some("api".to_string()),
+            owner: None,
+            source: super::super::ResolutionSource::Flag,
+            config_path: None,
+        };
+        let secret = Secret {
+            name: "A".to_string(),
+            value: "1".to_string(),
+        };
+        let items = [RevealedSecret::from(&secret)];
+        let envelope = Envelope::new(&resolution, OffsetDateTime::UNIX_EPOCH, &items).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(envelope).unwrap(),
+            serde_json::json!({
+                "app": {"id": "da_1", "name": "api"},
+                "fetched_at": "1970-01-01T00:00:00Z",
+                "count": 1,
+                "secrets": [{"name": "A", "value": "1"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_output_version() {
+        assert_eq!(OutputVersion::parse("1"), Ok(OutputVersion::V1));
+        assert!(OutputVersion::parse("2").is_err());
+        assert!(OutputVersion::parse("latest").is_err());
+        assert_eq!(
+            OutputVersionOpts::default().version(),
+            OutputVersion::LATEST
+        );
+
+        let value = serde_json::json!({"secrets": [{"name": "A"}]});
+        assert_eq!(
+            render_versioned(ListFormat::Json, OutputVersion::V1, &value).unwrap(),
+            render_structured(ListFormat::Json, &value).unwrap()
+        );
+        assert!(render_versioned(ListFormat::Table, OutputVersion::V1, &value).is_err());
+        // Serialization errors are reported, not rendered as an empty document.
+        let unserializable = std::collections::BTreeMap::from([((1, 2), 3)]);
+        assert!(render_versioned(ListFormat::Json, OutputVersion::V1, &unserializable).is_err());
+    }
+
+    #[test]
+    fn test_revealed_secret_entries() {
+        let secret = Secret {
+            name: "A".to_string(),
+            value: "1".to_string(),
+        };
+        let empty = Secret {
+            name: "C".to_string(),
+            value: String::new(),
+        };
+        let err = SecretError {
+            name: "B".to_string(),
+            error: anyhow::anyhow!("permission denied"),
+        };
+        let items = [
+            RevealedSecret::from(&secret),
+            RevealedSecret::from(&err),
+            RevealedSecret::from(&empty),
+        ];
+
+        // Empty values are kept, while unrevealed secrets have no value at all.
+        assert_eq!(
+            serde_json::to_value(items).unwrap(),
+            serde_json::json!([
+                {"name": "A", "value": "1"},
+                {"name": "B", "error": "permission denied"},
+                {"name": "C", "value": ""},
+            ])
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use super::Secret;
+use crate::utils::render::ListFormat;
+use colored::Colorize;
+
+/// The part of a secret a [`ValidationError`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ValidationField {
+    Name,
+    Value,
+}
+
+impl std::fmt::Display for ValidationField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Name => write!(f, "name"),
+            Self::Value => write!(f, "value"),
+        }
+    }
+}
+
+/// An invalid secret name or value.
+///
+/// NOTE: this never contains the value of the secret, so it is safe to print.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, thiserror::Error)]
+#[error("invalid {field} for secret '{secret}': {reason}")]
+pub(crate) struct ValidationError {
+    /// The name of the secret.
+    pub secret: String,
+    pub field: ValidationField,
+    pub reason: String,
+    /// A hint on how to fix the error, if there is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl ValidationError {
+    fn name(secret: &str, reason: impl Into<String>, suggestion: Option<String>) -> Self {
+        Self {
+            secret: secret.to_string(),
+            field: ValidationField::Name,
+            reason: reason.into(),
+            suggestion,
+        }
+    }
+}
+
+/// All the validation errors of a batch of secrets.
+#[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(transparent)]
+pub(crate) struct ValidationErrors(pub Vec<ValidationError>);
+
+impl std::fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} invalid secret(s):", self.0.len())?;
+        for err in &self.0 {
+            write!(f, "\n  - {err}")?;
+            if let Some(suggestion) = &err.suggestion {
+                write!(f, "\n    {}: {suggestion}", "Hint".bold())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl ValidationErrors {
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// Check that the given name can be used as the name of a secret, i.e. that it is a valid
+/// environment variable name.
+pub(crate) fn validate_secret_name(name: &str) -> Result<(), ValidationError> {
+    let suggestion = || {
+        let fixed: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if fixed.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("use '_{fixed}' instead")
+        } else {
+            format!("use '{fixed}' instead")
+        }
+    };
+
+    match name.chars().next() {
+        None => {
+            return Err(ValidationError::name(
+                name,
+                "secret names cannot be empty",
+                None,
+            ))
+        }
+        Some(c) if c.is_ascii_digit() => {
+            return Err(ValidationError::name(
+                name,
+                "secret names cannot start with a digit",
+                Some(suggestion()),
+            ))
+        }
+        _ => {}
+    }
+
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_'))
+    {
+        return Err(ValidationError::name(
+            name,
+            format!("secret names can only contain ASCII letters, digits and '_', found '{c}'"),
+            Some(suggestion()),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that the given value can be used as the value of a secret.
+///
+/// Empty values are only accepted with `allow_empty`.
+pub(crate) fn validate_secret_value(
+    name: &str,
+    value: &str,
+    allow_empty: bool,
+) -> Result<(), ValidationError> {
+    if value.is_empty() && !allow_empty {
+        return Err(ValidationError {
+            secret: name.to_string(),
+            field: ValidationField::Value,
+            reason: "the value is empty".to_string(),
+            suggestion: Some("pass `--allow-empty` if this is intended".to_string()),
+        });
+    }
+    if value.contains('\0') {
+        return Err(ValidationError {
+            secret: name.to_string(),
+            field: ValidationField::Value,
+            reason: "secret values cannot contain NUL characters".to_string(),
+            suggestion: Some("encode binary values, e.g. with base64".to_string()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Validate the values of secrets about to be created or updated.
+///
+/// Names are not checked: the backend accepts names that are not valid
+/// environment variable names, such as `db.url`. Only the flows producing new
+/// names (`--rename`, the target of `copy`) use [`validate_secret_name`].
+pub(crate) fn validate_secret_values(
+    secrets: &[Secret],
+    allow_empty: bool,
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::default();
+    for secret in secrets {
+        errors
+            .0
+            .extend(validate_secret_value(&secret.name, &secret.value, allow_empty).err());
+    }
+    errors.into_result()
+}
+
+/// Report the validation errors contained in `err`, if any.
+///
+/// For structured formats (json, yaml) the errors are printed to stdout, and
+/// only a summary is kept in the returned error. Otherwise the error is
+/// returned as is, as it already renders as human-readable messages.
+pub(crate) fn report(err: anyhow::Error, format: Option<ListFormat>) -> anyhow::Error {
+    let Some(errors) = err.downcast_ref::<ValidationErrors>() else {
+        return err;
+    };
+    let Some(format) = format.filter(|f| super::render::is_structured(*f)) else {
+        return err;
+    };
+    let out = match super::render::render_structured(format, errors) {
+        Ok(out) => out,
+        Err(render_err) => {
+            return err.context(format!("could not render the errors: {render_err:#}"));
+        }
+    };
+
+    println!("{out}");
+    anyhow::anyhow!("{} invalid secret(s)", errors.0.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_secret_name() {
+        assert!(validate_secret_name("DB_URL").is_ok());
+        assert!(validate_secret_name("_1").is_ok());
+
+        let err = validate_secret_name("1DB").unwrap_err();
+        assert_eq!(err.field, ValidationField::Name);
+        assert_eq!(err.suggestion.as_deref(), Some("use '_1DB' instead"));
+
+        let err = validate_secret_name("db.url").unwrap_err();
+        assert_eq!(err.suggestion.as_deref(), Some("use 'db_url' instead"));
+
+        assert_eq!(validate_secret_name("").unwrap_err().suggestion, None);
+    }
+
+    #[test]
+    fn test_validation_errors_are_aggregated() {
+        let secret = |name: &str, value: &str| Secret {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let secrets = [
+            secret("OK", "1"),
+            secret("db.url", "2"),
+            secret("EMPTY", ""),
+            secret("BAD_VALUE", "a\0b"),
+            secret("1BAD", "also\0bad"),
+        ];
+
+        // Names are not checked, only values.
+        let errors = validate_secret_values(&secrets, false).unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&errors).unwrap(),
+            serde_json::json!([
+                {
+                    "secret": "EMPTY",
+                    "field": "value",
+                    "reason": "the value is empty",
+                    "suggestion": "pass `--allow-empty` if this is intended",
+                },
+                {
+                    "secret": "BAD_VALUE",
+                    "field": "value",
+                    "reason": "secret values cannot contain NUL characters",
+                    "suggestion": "encode binary values, e.g. with base64",
+                },
+                {
+                    "secret": "1BAD",
+                    "field": "value",
+                    "reason": "secret values cannot contain NUL characters",
+                    "suggestion": "encode binary values, e.g. with base64",
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_empty_values() {
+        let secrets = [Secret {
+            name: "FLAG".to_string(),
+            value: String::new(),
+        }];
+
+        let errors = validate_secret_values(&secrets, false).unwrap_err();
+        assert_eq!(errors.0[0].reason, "the value is empty");
+        assert!(validate_secret_values(&secrets, true).is_ok());
+    }
+}
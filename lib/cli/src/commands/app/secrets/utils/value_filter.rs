@@ -0,0 +1,166 @@
+use super::Secret;
+use std::{process::Stdio, time::Duration};
+use tokio::io::AsyncWriteExt;
+
+/// Flags to transform secret values with an external command.
+#[derive(clap::Parser, Debug, Clone, Default)]
+pub struct ValueFilterOpts {
+    /// Pipe every secret value through the given shell command, and use what it prints to
+    /// stdout as the value instead (e.g. `base64 -d`). A single trailing newline is removed.
+    ///
+    /// The name of the secret is available to the command as `$SECRET_NAME`.
+    ///
+    /// WARNING: the command runs with your permissions and sees every value in clear text.
+    /// Anything it logs, stores or sends somewhere leaks your secrets, so only use commands
+    /// you trust. Requires `--i-understand-value-filter`.
+    #[clap(long, value_name = "COMMAND", requires = "i_understand_value_filter")]
+    pub value_filter: Option<String>,
+
+    /// Confirm that the command given with `--value-filter` is trusted with all secret values.
+    #[clap(long, requires = "value_filter")]
+    pub i_understand_value_filter: bool,
+
+    /// How long the `--value-filter` command may take for a single value, in seconds.
+    #[clap(long, value_name = "SECONDS", default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub value_filter_timeout: u64,
+}
+
+impl ValueFilterOpts {
+    /// Run the filter, if any, on the value of every secret.
+    ///
+    /// All secrets are processed; if the filter fails for any of them, the
+    /// returned error lists every failure.
+    pub(in crate::commands::app::secrets) async fn apply(
+        &self,
+        secrets: Vec<Secret>,
+    ) -> anyhow::Result<Vec<Secret>> {
+        let Some(command) = &self.value_filter else {
+            return Ok(secrets);
+        };
+        let timeout = Duration::from_secs(self.value_filter_timeout);
+
+        let mut filtered = Vec::with_capacity(secrets.len());
+        let mut failures = Vec::new();
+        for secret in secrets {
+            match run_filter(command, &secret, timeout).await {
+                Ok(value) => filtered.push(Secret {
+                    name: secret.name,
+                    value,
+                }),
+                Err(err) => failures.push(format!("{}: {err:#}", secret.name)),
+            }
+        }
+
+        if !failures.is_empty() {
+            anyhow::bail!(
+                "The value filter failed for {} secret(s):\n  {}",
+                failures.len(),
+                failures.join("\n  ")
+            );
+        }
+        Ok(filtered)
+    }
+}
+
+/// Run `command` with the value of `secret` on stdin, and return its stdout.
+///
+/// NOTE: errors must never contain the value or the output of the command, as
+/// the latter is likely to be derived from the value.
+async fn run_filter(command: &str, secret: &Secret, timeout: Duration) -> anyhow::Result<String> {
+    let mut child = shell(command)
+        .env("SECRET_NAME", &secret.name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("could not run the value filter: {e}"))?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let value = secret.value.as_bytes();
+    let write = async move {
+        // A filter that does not read its input is not an error by itself.
+        match stdin.write_all(value).await {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e),
+            _ => Ok(()),
+        }
+    };
+
+    let (written, output) = tokio::time::timeout(timeout, async {
+        tokio::join!(write, child.wait_with_output())
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out after {}s", timeout.as_secs()))?;
+    written.map_err(|e| anyhow::anyhow!("could not write the value to the filter: {e}"))?;
+    let output = output.map_err(|e| anyhow::anyhow!("the value filter failed: {e}"))?;
+
+    if !output.status.success() {
+        anyhow::bail!("the value filter exited with {}", output.status);
+    }
+    let mut value = String::from_utf8(output.stdout)
+        .map_err(|_| anyhow::anyhow!("the value filter did not print valid UTF-8"))?;
+    if value.ends_with('\n') {
+        value.pop();
+        if value.ends_with('\r') {
+            value.pop();
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn opts(command: &str) -> ValueFilterOpts {
+        ValueFilterOpts {
+            value_filter: Some(command.to_string()),
+            i_understand_value_filter: true,
+            value_filter_timeout: 5,
+        }
+    }
+
+    fn secret(name: &str, value: &str) -> Secret {
+        Secret {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_value_filter() {
+        let secrets = opts("tr a-z A-Z; echo \"@$SECRET_NAME\"")
+            .apply(vec![secret("A", "one"), secret("B", "two")])
+            .await
+            .unwrap();
+        assert_eq!(secrets[0].value, "ONE@A");
+        assert_eq!(secrets[1].value, "TWO@B");
+    }
+
+    #[tokio::test]
+    async fn test_value_filter_failures() {
+        let err = opts("test \"$SECRET_NAME\" = A || exit 3; cat")
+            .apply(vec![secret("A", "hunter2"), secret("B", "hunter3")])
+            .await
+            .err()
+            .expect("B should fail")
+            .to_string();
+        assert!(err.contains("1 secret(s)"), "{err}");
+        assert!(err.contains("B: the value filter exited with"), "{err}");
+        assert!(!err.contains("hunter"), "{err}");
+    }
+}
@@ -0,0 +1,77 @@
+use colored::Colorize;
+
+/// A warning raised while running a command.
+///
+/// NOTE: the message must never contain the value of a secret, and should be
+/// plain text, as it also ends up in structured output.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub(crate) struct Warning {
+    /// A stable identifier of the kind of warning, e.g. `unreadable_secret`.
+    pub code: &'static str,
+    pub message: String,
+    /// The secret the warning is about, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+/// The warnings collected while running a command.
+///
+/// In human mode they are printed to stderr; structured formats can carry them
+/// in the output instead (see [`super::render::Envelope`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(transparent)]
+pub(crate) struct Warnings(Vec<Warning>);
+
+impl Warnings {
+    pub fn push(&mut self, code: &'static str, message: impl Into<String>) {
+        self.0.push(Warning {
+            code,
+            message: message.into(),
+            secret: None,
+        });
+    }
+
+    pub fn push_for_secret(
+        &mut self,
+        code: &'static str,
+        secret: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.0.push(Warning {
+            code,
+            message: message.into(),
+            secret: Some(secret.into()),
+        });
+    }
+
+    pub fn as_slice(&self) -> &[Warning] {
+        &self.0
+    }
+
+    /// Print all warnings to stderr.
+    pub fn print(&self) {
+        for warning in &self.0 {
+            eprintln!("{}: {}", "Warning".bold().yellow(), warning.message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warnings_serialization() {
+        let mut warnings = Warnings::default();
+        warnings.push("ignored_flag", "`--confirm-token` has no effect");
+        warnings.push_for_secret("unreadable_secret", "DB_URL", "permission denied");
+
+        assert_eq!(
+            serde_json::to_value(&warnings).unwrap(),
+            serde_json::json!([
+                {"code": "ignored_flag", "message": "`--confirm-token` has no effect"},
+                {"code": "unreadable_secret", "message": "permission denied", "secret": "DB_URL"},
+            ])
+        );
+    }
+}
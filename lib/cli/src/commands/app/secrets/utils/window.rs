@@ -0,0 +1,97 @@
+use super::metadata::MetadataCheck;
+use crate::utils::timestamp::parse_timestamp_or_relative_time_negative_offset;
+use time::OffsetDateTime;
+use wasmer_backend_api::types::Secret as BackendSecret;
+
+/// Flags to only select the secrets last updated within a time window.
+#[derive(clap::Parser, Debug, Clone, Default)]
+pub struct UpdateWindowOpts {
+    /// Only include secrets last updated at or after the given time.
+    ///
+    /// Accepts RFC 3339 and RFC 2822 timestamps, dates (`2024-01-01`), unix timestamps and
+    /// relative times (`7d` for 7 days ago).
+    #[clap(long, value_parser = parse_timestamp_or_relative_time_negative_offset)]
+    pub since: Option<OffsetDateTime>,
+
+    /// Only include secrets last updated before the given time.
+    ///
+    /// Accepts the same formats as `--since`.
+    #[clap(long, value_parser = parse_timestamp_or_relative_time_negative_offset)]
+    pub until: Option<OffsetDateTime>,
+}
+
+impl UpdateWindowOpts {
+    pub fn is_set(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    /// Check that the window is not empty.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            if since >= until {
+                anyhow::bail!("`--since` must be before `--until`.");
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the given secret was last updated within the window.
+    ///
+    /// Secrets whose update time is not known are kept, unless `check` is
+    /// strict.
+    pub fn contains(&self, secret: &BackendSecret, check: &MetadataCheck) -> anyhow::Result<bool> {
+        if !self.is_set() {
+            return Ok(true);
+        }
+
+        let Some(updated_at) = check.updated_at(
+            secret,
+            "--since/--until",
+            "they were included regardless of their update time",
+        )?
+        else {
+            return Ok(true);
+        };
+        Ok(self.since.map_or(true, |since| updated_at >= since)
+            && self.until.map_or(true, |until| updated_at < until))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn secret(updated_at: &str) -> BackendSecret {
+        super::super::backend_secret("A", updated_at)
+    }
+
+    #[test]
+    fn test_update_window() {
+        let window = UpdateWindowOpts {
+            since: Some(datetime!(2024-01-01 0:00 UTC)),
+            until: Some(datetime!(2024-02-01 0:00 UTC)),
+        };
+        let check = MetadataCheck::new(true);
+        assert!(window
+            .contains(&secret("2024-01-01T00:00:00Z"), &check)
+            .unwrap());
+        assert!(window
+            .contains(&secret("2024-01-15T12:00:00Z"), &check)
+            .unwrap());
+        assert!(!window
+            .contains(&secret("2024-02-01T00:00:00Z"), &check)
+            .unwrap());
+        assert!(!window
+            .contains(&secret("2023-12-31T23:59:59Z"), &check)
+            .unwrap());
+        assert!(window.contains(&secret(""), &check).is_err());
+        assert!(window
+            .contains(&secret(""), &MetadataCheck::new(false))
+            .unwrap());
+
+        assert!(UpdateWindowOpts::default()
+            .contains(&secret(""), &check)
+            .unwrap());
+    }
+}
@@ -17,7 +17,7 @@ use crate::{
 
 /// App identifier.
 ///
-/// Can be either a namespace/name a plain name or an app id.
+/// Can be either a namespace/name a plain name, an app id or the URL of the app.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum AppIdent {
     /// Backend app id like "da_xxysw34234"
@@ -26,23 +26,26 @@ pub enum AppIdent {
     AppVersionId(String),
     NamespacedName(String, String),
     Name(String),
+    /// The host name of the URL of an app, like "my-app-xyz.wasmer.app"
+    Url(String),
 }
 
 impl AppIdent {
     /// Resolve an app identifier through the API.
+    ///
+    /// Fails with an explanatory message if the app does not exist, or if the
+    /// current user is not allowed to see it.
     pub async fn resolve(&self, client: &WasmerClient) -> Result<DeployApp, anyhow::Error> {
-        match self {
+        let app = match self {
             AppIdent::AppId(app_id) => {
-                wasmer_backend_api::query::get_app_by_id(client, app_id.clone())
-                    .await
-                    .with_context(|| format!("Could not find app with id '{}'", app_id))
+                wasmer_backend_api::query::get_app_by_id_opt(client, app_id.clone()).await
             }
             AppIdent::AppVersionId(id) => {
                 let (app, _version) =
                     wasmer_backend_api::query::get_app_version_by_id_with_app(client, id.clone())
                         .await
                         .with_context(|| format!("Could not query for app version id '{}'", id))?;
-                Ok(app)
+                return Ok(app);
             }
             AppIdent::Name(name) => {
                 // The API only allows to query by owner + name,
@@ -52,24 +55,135 @@ impl AppIdent {
                     .await?
                     .context("not logged in")?;
 
-                wasmer_backend_api::query::get_app(client, user.username, name.clone())
-                    .await?
-                    .with_context(|| format!("Could not find app with name '{name}'"))
+                wasmer_backend_api::query::get_app(client, user.username, name.clone()).await
             }
             AppIdent::NamespacedName(owner, name) => {
-                wasmer_backend_api::query::get_app(client, owner.clone(), name.clone())
-                    .await?
-                    .with_context(|| format!("Could not find app '{owner}/{name}'"))
+                wasmer_backend_api::query::get_app(client, owner.clone(), name.clone()).await
+            }
+            AppIdent::Url(host) => Self::resolve_host(client, host).await,
+        };
+
+        match app {
+            Ok(Some(app)) => Ok(app),
+            Ok(None) => Err(self.not_found()),
+            Err(err) => match AppLookupFailure::from_error(&err) {
+                Some(AppLookupFailure::NotFound) => Err(err.context(self.not_found())),
+                Some(AppLookupFailure::Unauthorized) => Err(err.context(format!(
+                    "You are not allowed to access app {self}.\n\
+                     {}: make sure you are logged in as the right user (see `wasmer whoami`) and \
+                     that you are a member of the app's namespace.",
+                    "Hint".bold()
+                ))),
+                None => Err(err.context(format!("Could not load app {self}"))),
+            },
+        }
+    }
+
+    /// Look up the app served at the given host name through its alias.
+    ///
+    /// Apps on the Edge are served at `<alias>.<domain>`, so the first label of
+    /// the host is tried first. The whole host is tried next, for aliases that
+    /// are domains themselves.
+    async fn resolve_host(
+        client: &WasmerClient,
+        host: &str,
+    ) -> Result<Option<DeployApp>, anyhow::Error> {
+        let mut candidates = vec![host];
+        if let Some((label, _)) = host.split_once('.') {
+            candidates.insert(0, label);
+        }
+
+        for alias in candidates {
+            tracing::debug!(%alias, "looking up app by alias");
+            let app =
+                wasmer_backend_api::query::get_app_by_alias(client, alias.to_string()).await?;
+            if app.is_some() {
+                return Ok(app);
+            }
+        }
+        Ok(None)
+    }
+
+    fn not_found(&self) -> anyhow::Error {
+        let hint = match self {
+            AppIdent::Name(_) => {
+                "check the spelling of the name, or prefix it with its owner as `<owner>/<name>` \
+                 if the app is not yours. Run `wasmer app list` to see your apps."
             }
+            _ => "check the spelling of the name and owner, or run `wasmer app list` to see your apps.",
+        };
+        anyhow::anyhow!("App {self} does not exist.\n{}: {hint}", "Hint".bold())
+    }
+}
+
+impl std::fmt::Display for AppIdent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppIdent::AppId(id) | AppIdent::AppVersionId(id) => write!(f, "'{id}'"),
+            AppIdent::NamespacedName(owner, name) => write!(f, "'{owner}/{name}'"),
+            AppIdent::Name(name) => write!(f, "'{name}'"),
+            AppIdent::Url(host) => write!(f, "'https://{host}'"),
+        }
+    }
+}
+
+/// Why an app could not be looked up, as told by the errors of the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AppLookupFailure {
+    NotFound,
+    Unauthorized,
+}
+
+impl AppLookupFailure {
+    fn from_error(err: &anyhow::Error) -> Option<Self> {
+        let failure = err.downcast_ref::<wasmer_backend_api::GraphQLApiFailure>()?;
+        // Unauthorized takes precedence: some servers answer "not found" for
+        // apps the user is not allowed to see, but not the other way around.
+        let messages = || failure.errors.iter().map(|e| e.message.as_str());
+        if messages().any(Self::is_unauthorized) {
+            Some(Self::Unauthorized)
+        } else if messages().any(Self::is_not_found) {
+            Some(Self::NotFound)
+        } else {
+            None
         }
     }
+
+    fn is_unauthorized(message: &str) -> bool {
+        let message = message.to_lowercase();
+        [
+            "permission",
+            "not authorized",
+            "unauthorized",
+            "forbidden",
+            "access denied",
+            "not allowed",
+        ]
+        .iter()
+        .any(|pattern| message.contains(pattern))
+    }
+
+    fn is_not_found(message: &str) -> bool {
+        let message = message.to_lowercase();
+        ["not found", "does not exist", "doesn't exist"]
+            .iter()
+            .any(|pattern| message.contains(pattern))
+    }
 }
 
 impl std::str::FromStr for AppIdent {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((namespace, name)) = s.split_once('/') {
+        if s.starts_with("https://") || s.starts_with("http://") {
+            let url = url::Url::parse(s)
+                .map_err(|e| anyhow::anyhow!("invalid app identifier '{s}': invalid URL: {e}"))?;
+            let Some(host) = url.host_str() else {
+                bail!("invalid app identifier '{s}': the URL has no host");
+            };
+
+            Ok(Self::Url(host.to_string()))
+        } else if let Some((namespace, name)) = s.split_once('/') {
             if namespace.is_empty() {
                 bail!("invalid app identifier '{s}': namespace can not be empty");
             }
@@ -116,6 +230,7 @@ pub struct AppIdentOpts {
     /// - namespace/app-name
     /// - app-alias
     /// - App ID
+    /// - App URL (e.g. https://my-app.wasmer.app)
     pub app: Option<AppIdent>,
 }
 
@@ -197,6 +312,27 @@ mod tests {
             AppIdent::from_str("alpha/beta").unwrap(),
             AppIdent::NamespacedName("alpha".to_string(), "beta".to_string()),
         );
+
+        assert_eq!(
+            AppIdent::from_str("https://my-app-xyz.wasmer.app/some/path").unwrap(),
+            AppIdent::Url("my-app-xyz.wasmer.app".to_string()),
+        );
+        assert!(AppIdent::from_str("https://").is_err());
+    }
+
+    #[test]
+    fn test_app_lookup_failure_messages() {
+        assert!(AppLookupFailure::is_unauthorized(
+            "You do not have permission to view this app"
+        ));
+        assert!(AppLookupFailure::is_unauthorized("Forbidden"));
+        assert!(!AppLookupFailure::is_unauthorized("App not found"));
+
+        assert!(AppLookupFailure::is_not_found(
+            "DeployApp matching query does not exist."
+        ));
+        assert!(AppLookupFailure::is_not_found("App not found"));
+        assert!(!AppLookupFailure::is_not_found("Internal server error"));
     }
 }
 
@@ -212,6 +348,7 @@ pub struct AppIdentFlag {
     /// - namespace/app-name
     /// - app-alias
     /// - App ID
+    /// - App URL (e.g. https://my-app.wasmer.app)
     #[clap(long)]
     pub app: Option<AppIdent>,
 }
@@ -294,16 +431,39 @@ pub fn get_app_config_from_current_dir() -> Result<(AppConfigV1, std::path::Path
 }
 
 /// Prompt for an app ident.
+///
+/// The syntax of the ident is checked when the input is submitted, and the
+/// user is asked again if it is invalid.
 #[allow(dead_code)]
 pub(crate) fn prompt_app_ident(message: &str) -> Result<AppIdent, anyhow::Error> {
     let theme = ColorfulTheme::default();
+    let ident: String = dialoguer::Input::with_theme(&theme)
+        .with_prompt(message)
+        .validate_with(|input: &String| AppIdent::from_str(input).map(|_| ()))
+        .interact_text()?;
+    AppIdent::from_str(&ident)
+}
+
+/// Prompt for an app, and look it up through the API.
+///
+/// If the app cannot be resolved, the error is printed and the user is asked
+/// again.
+pub(crate) async fn prompt_app(
+    client: &WasmerClient,
+    message: &str,
+) -> Result<DeployApp, anyhow::Error> {
     loop {
-        let ident: String = dialoguer::Input::with_theme(&theme)
-            .with_prompt(message)
-            .interact_text()?;
-        match AppIdent::from_str(&ident) {
-            Ok(id) => break Ok(id),
-            Err(e) => eprintln!("{e}"),
+        let ident = prompt_app_ident(message)?;
+
+        let pb = indicatif::ProgressBar::new_spinner();
+        pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        pb.set_message(format!("Looking up app {ident}..."));
+        let res = ident.resolve(client).await;
+        pb.finish_and_clear();
+
+        match res {
+            Ok(app) => break Ok(app),
+            Err(e) => eprintln!("{}: {e:#}", "Error".bold().red()),
         }
     }
 }
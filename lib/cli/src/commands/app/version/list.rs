@@ -102,7 +102,7 @@ impl AsyncCliCommand for CmdAppVersionList {
                 .collect::<Vec<_>>()
         };
 
-        println!("{}", self.fmt.format.render(&versions));
+        println!("{}", self.fmt.get(&self.env).render(&versions));
 
         Ok(())
     }
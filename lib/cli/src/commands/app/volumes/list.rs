@@ -31,7 +31,7 @@ impl AsyncCliCommand for CmdAppVolumesList {
         if volumes.is_empty() {
             eprintln!("App {} has no volumes!", app.name);
         } else {
-            println!("{}", self.fmt.format.render(volumes.as_slice()));
+            println!("{}", self.fmt.get(&self.env).render(volumes.as_slice()));
         }
 
         Ok(())
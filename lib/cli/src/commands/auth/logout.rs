@@ -1,6 +1,6 @@
 use crate::{
     commands::AsyncCliCommand,
-    config::{WasmerConfig, WasmerEnv, DEFAULT_PROD_REGISTRY},
+    config::{WasmerEnv, DEFAULT_PROD_REGISTRY},
 };
 use colored::Colorize;
 use is_terminal::IsTerminal;
@@ -68,7 +68,7 @@ impl AsyncCliCommand for Logout {
                     _ = config.registry.set_current_registry(&new_reg).await;
                 }
             }
-            let path = WasmerConfig::get_file_location(self.env.dir());
+            let path = self.env.config_file_path();
             config.save(path)?;
 
             // Read it again..
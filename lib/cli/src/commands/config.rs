@@ -1,5 +1,5 @@
 use crate::{
-    config::{UpdateRegistry, WasmerConfig, WasmerEnv},
+    config::{UpdateRegistry, WasmerEnv},
     VERSION,
 };
 use anyhow::{Context, Result};
@@ -87,6 +87,9 @@ pub enum RetrievableConfigField {
     /// Print the proxy URL
     #[clap(name = "proxy.url")]
     ProxyUrl,
+    /// Print the default format of commands listing items
+    #[clap(name = "output.format")]
+    OutputFormat,
 }
 
 /// Setting that can be stored in the wasmer config
@@ -107,6 +110,9 @@ pub enum StorableConfigField {
     /// Set the active proxy URL
     #[clap(name = "proxy.url")]
     ProxyUrl(SetProxyUrl),
+    /// Set the default format of commands listing items
+    #[clap(name = "output.format")]
+    OutputFormat(SetOutputFormat),
 }
 
 /// Set the current active registry URL
@@ -164,6 +170,15 @@ pub struct SetProxyUrl {
     pub url: String,
 }
 
+/// Set the default format of commands listing items
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Parser)]
+pub struct SetOutputFormat {
+    /// The format to use when `--format` is not given: yaml, json, table or item-table
+    /// (empty = unset)
+    #[clap(name = "FORMAT")]
+    pub format: String,
+}
+
 #[async_trait::async_trait]
 impl AsyncCliCommand for Config {
     type Output = ();
@@ -227,7 +242,7 @@ impl Config {
         }
 
         if flags.config_path {
-            let path = WasmerConfig::get_file_location(self.env.dir());
+            let path = self.env.config_file_path();
             println!("{}", path.display());
         }
 
@@ -237,7 +252,7 @@ impl Config {
 
 impl GetOrSet {
     async fn execute(&self, env: &WasmerEnv) -> Result<()> {
-        let config_file = WasmerConfig::get_file_location(env.dir());
+        let config_file = env.config_file_path();
         let mut config = env.config()?;
 
         match self {
@@ -266,6 +281,13 @@ impl GetOrSet {
                         println!("none");
                     }
                 }
+                RetrievableConfigField::OutputFormat => {
+                    if let Some(s) = config.output.format.as_ref() {
+                        println!("{s}");
+                    } else {
+                        println!("none");
+                    }
+                }
             },
             GetOrSet::Set(s) => {
                 match s {
@@ -302,6 +324,16 @@ impl GetOrSet {
                     StorableConfigField::UpdateNotificationsEnabled(u) => {
                         config.update_notifications_enabled = u.enabled.0;
                     }
+                    StorableConfigField::OutputFormat(f) => {
+                        if f.format == "none" || f.format.is_empty() {
+                            config.output.format = None;
+                        } else {
+                            f.format
+                                .parse::<crate::utils::render::ListFormat>()
+                                .map_err(anyhow::Error::msg)?;
+                            config.output.format = Some(f.format.clone());
+                        }
+                    }
                 }
                 config
                     .save(config_file)
@@ -30,7 +30,7 @@ impl AsyncCliCommand for CmdDomainList {
             },
         )
         .await?;
-        println!("{}", self.fmt.format.render(&domains));
+        println!("{}", self.fmt.get(&self.env).render(&domains));
         Ok(())
     }
 }
@@ -18,7 +18,7 @@ impl AsyncCliCommand for CmdNamespaceList {
 
         let namespaces = wasmer_backend_api::query::user_namespaces(&client).await?;
 
-        println!("{}", self.fmt.format.render(&namespaces));
+        println!("{}", self.fmt.get(&self.env).render(&namespaces));
 
         Ok(())
     }
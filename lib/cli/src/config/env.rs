@@ -3,7 +3,7 @@ use anyhow::{Context, Error};
 use lazy_static::lazy_static;
 use std::path::{Path, PathBuf};
 use url::Url;
-use wasmer_backend_api::WasmerClient;
+use wasmer_backend_api::{retry::RetryPolicy, WasmerClient};
 
 lazy_static! {
     pub static ref DEFAULT_WASMER_CLI_USER_AGENT: String =
@@ -33,6 +33,35 @@ pub struct WasmerEnv {
     /// the environment by default)
     #[clap(long, env = "WASMER_TOKEN")]
     token: Option<String>,
+
+    /// Path to an alternate Wasmer config file to use instead of
+    /// `$WASMER_DIR/wasmer.toml`
+    #[clap(long = "config", env = "WASMER_CONFIG")]
+    config_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    api: ApiClientOpts,
+}
+
+/// HTTP settings of the client used to communicate with the registry.
+#[derive(Debug, Clone, PartialEq, Default, clap::Parser)]
+pub struct ApiClientOpts {
+    /// Timeout for a whole request to the registry (e.g. "30s", "2m")
+    #[clap(long, env = "WASMER_API_TIMEOUT")]
+    api_timeout: Option<humantime::Duration>,
+
+    /// Maximum number of idle connections to the registry kept open for reuse
+    #[clap(long, env = "WASMER_API_POOL_SIZE")]
+    api_pool_size: Option<usize>,
+
+    /// Interval of TCP keep-alive probes on connections to the registry (e.g. "60s")
+    #[clap(long, env = "WASMER_API_KEEPALIVE")]
+    api_keepalive: Option<humantime::Duration>,
+
+    /// Number of times a request to the registry is retried after a transient error, such as a
+    /// timeout or a 5xx response
+    #[clap(long, env = "WASMER_API_RETRIES")]
+    api_retries: Option<u32>,
 }
 
 impl WasmerEnv {
@@ -47,6 +76,8 @@ impl WasmerEnv {
             registry,
             token,
             cache_dir,
+            config_file: None,
+            api: ApiClientOpts::default(),
         }
     }
 
@@ -81,6 +112,17 @@ impl WasmerEnv {
 
     /// Load the current Wasmer config.
     pub fn config(&self) -> Result<WasmerConfig, Error> {
+        if let Some(path) = &self.config_file {
+            // Contrary to the default location, an explicitly requested config
+            // file must exist.
+            return WasmerConfig::from_path(path).with_context(|| {
+                format!(
+                    "Unable to load the config file \"{}\" (set via --config or $WASMER_CONFIG)",
+                    path.display()
+                )
+            });
+        }
+
         WasmerConfig::from_file(self.dir())
             .map_err(Error::msg)
             .with_context(|| {
@@ -102,6 +144,14 @@ impl WasmerEnv {
             .map_err(Into::into)
     }
 
+    /// The location of the config file in use.
+    pub fn config_file_path(&self) -> PathBuf {
+        match &self.config_file {
+            Some(path) => path.clone(),
+            None => WasmerConfig::get_file_location(self.dir()),
+        }
+    }
+
     /// The directory all Wasmer artifacts are stored in.
     pub fn dir(&self) -> &Path {
         &self.wasmer_dir
@@ -139,13 +189,24 @@ impl WasmerEnv {
     pub fn client_unauthennticated(&self) -> Result<WasmerClient, anyhow::Error> {
         let registry_url = self.registry_endpoint()?;
 
-        let proxy = self.proxy()?;
+        let mut builder = WasmerClient::builder(registry_url, &DEFAULT_WASMER_CLI_USER_AGENT);
+        if let Some(proxy) = self.proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(timeout) = self.api.api_timeout {
+            builder = builder.timeout(timeout.into());
+        }
+        if let Some(size) = self.api.api_pool_size {
+            builder = builder.pool_max_idle_per_host(size);
+        }
+        if let Some(interval) = self.api.api_keepalive {
+            builder = builder.tcp_keepalive(interval.into());
+        }
+        if let Some(retries) = self.api.api_retries {
+            builder = builder.retry_policy(RetryPolicy::with_retries(retries));
+        }
 
-        let client = wasmer_backend_api::WasmerClient::new_with_proxy(
-            registry_url,
-            &DEFAULT_WASMER_CLI_USER_AGENT,
-            proxy,
-        )?;
+        let client = builder.build()?;
 
         let client = if let Some(token) = self.token() {
             client.with_auth_token(token)
@@ -173,6 +234,8 @@ impl Default for WasmerEnv {
             cache_dir: super::DEFAULT_WASMER_CACHE_DIR.clone(),
             registry: None,
             token: None,
+            config_file: None,
+            api: ApiClientOpts::default(),
         }
     }
 }
@@ -242,6 +305,8 @@ mod tests {
             registry: None,
             cache_dir: temp.path().join("cache").to_path_buf(),
             token: None,
+            config_file: None,
+            api: ApiClientOpts::default(),
         };
 
         assert_eq!(
@@ -262,6 +327,8 @@ mod tests {
             registry: None,
             cache_dir: temp.path().join("cache").to_path_buf(),
             token: Some("asdf".to_string()),
+            config_file: None,
+            api: ApiClientOpts::default(),
         };
 
         assert_eq!(
@@ -281,6 +348,8 @@ mod tests {
             registry: Some(UserRegistry::from("wasmer.wtf")),
             cache_dir: temp.path().join("cache").to_path_buf(),
             token: None,
+            config_file: None,
+            api: ApiClientOpts::default(),
         };
 
         assert_eq!(
@@ -301,6 +370,8 @@ mod tests {
             registry: Some(UserRegistry::from("wasmer.wtf")),
             cache_dir: temp.path().join("cache").to_path_buf(),
             token: Some("asdf".to_string()),
+            config_file: None,
+            api: ApiClientOpts::default(),
         };
 
         assert_eq!(
@@ -322,6 +393,8 @@ mod tests {
             registry: None,
             cache_dir: expected_cache_dir.clone(),
             token: None,
+            config_file: None,
+            api: ApiClientOpts::default(),
         };
 
         assert_eq!(
@@ -332,6 +405,59 @@ mod tests {
         assert_eq!(env.cache_dir(), expected_cache_dir);
     }
 
+    #[test]
+    fn override_config_file() {
+        let temp = TempDir::new().unwrap();
+        let config_file = temp.path().join("other.toml");
+        std::fs::write(
+            &config_file,
+            WASMER_TOML.replace("prod-token", "other-token"),
+        )
+        .unwrap();
+
+        let env = WasmerEnv {
+            wasmer_dir: temp.path().to_path_buf(),
+            registry: None,
+            cache_dir: temp.path().join("cache").to_path_buf(),
+            token: None,
+            config_file: Some(config_file.clone()),
+            api: ApiClientOpts::default(),
+        };
+
+        assert_eq!(env.config_file_path(), config_file);
+        assert_eq!(env.token().unwrap(), "other-token");
+
+        let env = WasmerEnv {
+            config_file: Some(temp.path().join("missing.toml")),
+            ..env
+        };
+        assert!(env.config().is_err());
+    }
+
+    #[test]
+    fn default_list_format_from_config() {
+        use crate::{opts::ListFormatOpts, utils::render::ListFormat};
+
+        let temp = TempDir::new().unwrap();
+        let config = format!("{WASMER_TOML}\n[output]\nformat = \"json\"\n");
+        std::fs::write(temp.path().join("wasmer.toml"), config).unwrap();
+
+        let env = WasmerEnv {
+            wasmer_dir: temp.path().to_path_buf(),
+            registry: None,
+            cache_dir: temp.path().join("cache").to_path_buf(),
+            token: None,
+            config_file: None,
+            api: ApiClientOpts::default(),
+        };
+
+        assert_eq!(ListFormatOpts::default().get(&env), ListFormat::Json);
+        let flag = ListFormatOpts {
+            format: Some(ListFormat::Yaml),
+        };
+        assert_eq!(flag.get(&env), ListFormat::Yaml);
+    }
+
     #[test]
     fn registries_have_public_url() {
         let temp = TempDir::new().unwrap();
@@ -357,6 +483,8 @@ mod tests {
                 registry: Some(UserRegistry::from(input)),
                 cache_dir: temp.path().join("cache").to_path_buf(),
                 token: None,
+                config_file: None,
+                api: ApiClientOpts::default(),
             };
 
             assert_eq!(want, &env.registry_public_url().unwrap().to_string())
@@ -42,6 +42,10 @@ pub struct WasmerConfig {
     /// The proxy to use when connecting to the Internet.
     #[serde(default)]
     pub proxy: Proxy,
+
+    /// Defaults for the output of commands.
+    #[serde(default, skip_serializing_if = "OutputConfig::is_empty")]
+    pub output: OutputConfig,
 }
 
 impl Default for WasmerConfig {
@@ -51,6 +55,7 @@ impl Default for WasmerConfig {
             update_notifications_enabled: true,
             registry: Default::default(),
             proxy: Default::default(),
+            output: Default::default(),
         }
     }
 }
@@ -60,6 +65,19 @@ pub struct Proxy {
     pub url: Option<String>,
 }
 
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Default)]
+pub struct OutputConfig {
+    /// The format used by commands listing items when `--format` is not
+    /// given (yaml, json, table, item-table).
+    pub format: Option<String>,
+}
+
+impl OutputConfig {
+    fn is_empty(&self) -> bool {
+        self.format.is_none()
+    }
+}
+
 /// Struct to store login tokens for multiple registry URLs
 /// inside of the wasmer.toml configuration file
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone)]
@@ -223,6 +241,19 @@ impl WasmerConfig {
         }
     }
 
+    /// Load the config from an explicit file path.
+    ///
+    /// NOTE: In contrast to [`Self::from_file`], this fails if the file does
+    /// not exist or can not be parsed.
+    pub fn from_path(path: &Path) -> Result<Self, anyhow::Error> {
+        use anyhow::Context;
+
+        let config_toml = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read config file at '{}'", path.display()))?;
+        toml::from_str(&config_toml)
+            .with_context(|| format!("Could not parse config file at '{}'", path.display()))
+    }
+
     /// Creates and returns the `WASMER_DIR` directory (or $HOME/.wasmer as a fallback)
     pub fn get_wasmer_dir() -> Result<PathBuf, String> {
         Ok(
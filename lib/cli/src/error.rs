@@ -18,6 +18,27 @@ impl PrettyError {
     }
 }
 
+/// An error that makes the CLI exit with a specific exit code.
+///
+/// If the message is empty, nothing is printed before exiting.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct ExitCodeError {
+    /// The code the process will exit with.
+    pub code: i32,
+    message: String,
+}
+
+impl ExitCodeError {
+    /// Create a new [`ExitCodeError`].
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
 /// A macro that prints a warning with nice colors
 #[macro_export]
 macro_rules! warning {
@@ -35,6 +56,9 @@ impl PrettyError {
         std::process::exit(match result {
             Ok(_t) => 0,
             Err(error) => {
+                if let Some(code) = Self::report_exit_code_error(&error) {
+                    std::process::exit(code);
+                }
                 let runtime: Option<&RuntimeError> = error.downcast_ref();
                 let trapcode = runtime.map(|e| e.clone().to_trap());
                 eprintln!("{:?}", PrettyError { error });
@@ -61,6 +85,9 @@ impl PrettyError {
         std::process::exit(match result {
             Ok(_t) => 0,
             Err(error) => {
+                if let Some(code) = Self::report_exit_code_error(&error) {
+                    std::process::exit(code);
+                }
                 eprintln!("{:?}", PrettyError { error });
                 // we don't use process:abort() here to avoid message from rust
                 // that could interfer with testing tools
@@ -71,6 +98,17 @@ impl PrettyError {
     }
 }
 
+impl PrettyError {
+    /// Print an [`ExitCodeError`] (if `error` is one) and return its exit code.
+    fn report_exit_code_error(error: &Error) -> Option<i32> {
+        let e: &ExitCodeError = error.downcast_ref()?;
+        if !e.message.is_empty() {
+            eprintln!("{}", format!("{}: {}", "error".red(), error).bold());
+        }
+        Some(e.code)
+    }
+}
+
 impl Debug for PrettyError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let error = &self.error;
@@ -1,11 +1,17 @@
 //! Logging functions for the debug feature.
 
+use std::path::PathBuf;
+
 use is_terminal::IsTerminal;
 use tracing::level_filters::LevelFilter;
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
 const WHITELISTED_LOG_TARGETS: &[&str] = &["wasmer", "wasmer_wasix", "virtual_fs"];
 
+/// The fields of the events of the API client that can hold raw requests or
+/// responses, and so secret values.
+const API_PAYLOAD_FIELDS: &[&str] = &["res", "data", "body", "err", "vars"];
+
 /// Control the output generated by the CLI.
 #[derive(Debug, Default, Clone, PartialEq, clap::Parser)]
 pub struct Output {
@@ -21,6 +27,10 @@ pub struct Output {
     /// When to display colored output.
     #[clap(long, default_value_t = clap::ColorChoice::Auto, global = true)]
     pub color: clap::ColorChoice,
+    /// Also write logs as JSON to the given file, using the same verbosity as
+    /// the logs on stderr, but always including warnings and errors.
+    #[clap(long, global = true, env = "WASMER_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
 }
 
 impl Output {
@@ -39,21 +49,80 @@ impl Output {
             .with_thread_ids(true)
             .with_writer(std::io::stderr);
 
-        let filter_layer = self.log_filter();
+        let file_layer = self.log_file_layer();
 
         match self.log_format {
             LogFormat::Text => tracing_subscriber::registry()
-                .with(filter_layer)
-                .with(fmt_layer.compact().with_target(true))
+                .with(file_layer)
+                .with(
+                    fmt_layer
+                        .compact()
+                        .with_target(true)
+                        .with_filter(self.log_filter()),
+                )
                 .init(),
             LogFormat::Json => tracing_subscriber::registry()
-                .with(filter_layer)
-                .with(fmt_layer.json().with_target(true))
+                .with(file_layer)
+                .with(
+                    fmt_layer
+                        .json()
+                        .with_target(true)
+                        .with_filter(self.log_filter()),
+                )
                 .init(),
         }
     }
 
+    /// The layer writing JSON logs to `--log-file`, if requested.
+    fn log_file_layer<S>(&self) -> Option<impl Layer<S>>
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        let path = self.log_file.as_ref()?;
+        let file = match std::fs::File::create(path) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Unable to open the log file at \"{}\": {e}", path.display());
+                return None;
+            }
+        };
+        Some(self.file_layer(std::sync::Mutex::new(file)))
+    }
+
+    fn file_layer<S, W>(&self, writer: W) -> impl Layer<S>
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+        W: for<'w> fmt::MakeWriter<'w> + Send + Sync + 'static,
+    {
+        // Trace-level logs of the API client contain raw GraphQL responses,
+        // which can include secret values, so they are never written to the
+        // log file, and neither are other events of the client with a payload.
+        let no_api_payloads = tracing_subscriber::filter::filter_fn(|meta| {
+            !(meta.target().starts_with("wasmer_backend_api")
+                && (*meta.level() == tracing::Level::TRACE
+                    || meta
+                        .fields()
+                        .iter()
+                        .any(|f| API_PAYLOAD_FIELDS.contains(&f.name()))))
+        });
+
+        fmt::layer()
+            .json()
+            .with_target(true)
+            .with_span_events(fmt::format::FmtSpan::CLOSE)
+            .with_ansi(false)
+            .with_thread_ids(true)
+            .with_writer(writer)
+            .with_filter(no_api_payloads)
+            .with_filter(self.env_filter(self.default_level().max(LevelFilter::WARN)))
+    }
+
     fn log_filter(&self) -> EnvFilter {
+        self.env_filter(self.default_level())
+    }
+
+    /// The level of the logs on stderr, unless overridden by `$RUST_LOG`.
+    fn default_level(&self) -> LevelFilter {
         let default_filters = [
             LevelFilter::OFF,
             LevelFilter::WARN,
@@ -61,11 +130,14 @@ impl Output {
             LevelFilter::DEBUG,
         ];
 
-        // First, we set up the default log level.
-        let default_level = default_filters
+        default_filters
             .get(self.verbose as usize)
             .copied()
-            .unwrap_or(LevelFilter::TRACE);
+            .unwrap_or(LevelFilter::TRACE)
+    }
+
+    fn env_filter(&self, default_level: LevelFilter) -> EnvFilter {
+        // First, we set up the default log level.
         let mut filter = EnvFilter::builder()
             .with_default_directive(default_level.into())
             .from_env_lossy();
@@ -125,3 +197,44 @@ pub enum LogFormat {
     /// Machine-readable logs.
     Json,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_log_file_never_contains_api_payloads() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let layer = Output::default().file_layer(move || writer.clone());
+
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "wasmer_backend_api::client", data = "s3cr3t", "partial");
+            tracing::error!(target: "wasmer_backend_api::client", err = "s3cr3t", "failed");
+            tracing::trace!(target: "wasmer_backend_api::client", res = "s3cr3t", "succeeded");
+            tracing::warn!(target: "wasmer_backend_api::client", "payload-free warning");
+            tracing::info!(target: "wasmer", "below the default level");
+        });
+
+        // Warnings are written even without `--verbose`.
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("payload-free warning"), "{logs}");
+        assert!(!logs.contains("s3cr3t"), "{logs}");
+        assert!(!logs.contains("below the default level"), "{logs}");
+    }
+}
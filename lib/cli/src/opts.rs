@@ -1,4 +1,7 @@
-use crate::utils::render::ItemFormat;
+use crate::{
+    config::WasmerEnv,
+    utils::render::{ItemFormat, ListFormat},
+};
 
 /// Formatting options for a single item.
 #[derive(clap::Parser, Debug, Default)]
@@ -36,9 +39,50 @@ pub struct ItemTableFormatOpts {
 }
 
 /// Formatting options for a list of items.
-#[derive(clap::Parser, Debug)]
+#[derive(clap::Parser, Debug, Default)]
 pub struct ListFormatOpts {
     /// Output format. (yaml, json, table, item-table)
-    #[clap(short = 'f', long, default_value = "table")]
-    pub format: crate::utils::render::ListFormat,
+    ///
+    /// Defaults to `$WASMER_OUTPUT_FORMAT`, then to the `output.format` setting of the config
+    /// file, and then to `table`.
+    ///
+    /// Code should usually use [`Self::get`] to resolve the default format.
+    #[clap(short = 'f', long)]
+    pub format: Option<ListFormat>,
+}
+
+impl ListFormatOpts {
+    /// The environment variable with the default output format.
+    pub const FORMAT_ENV: &'static str = "WASMER_OUTPUT_FORMAT";
+
+    /// Get the output format: the `--format` flag, `$WASMER_OUTPUT_FORMAT`, the
+    /// `output.format` setting of the config file, or `ListFormat::Table`, in
+    /// that order.
+    ///
+    /// Invalid defaults are ignored with a warning.
+    pub fn get(&self, env: &WasmerEnv) -> ListFormat {
+        if let Some(format) = self.format {
+            return format;
+        }
+
+        let from_env = std::env::var(Self::FORMAT_ENV)
+            .ok()
+            .map(|value| (value, format!("${}", Self::FORMAT_ENV)));
+        let from_config = || {
+            let config = env
+                .config()
+                .map_err(|e| tracing::debug!(error = &*e, "unable to load the config"))
+                .ok()?;
+            let value = config.output.format?;
+            Some((value, "output.format in the config file".to_string()))
+        };
+
+        match from_env.or_else(from_config) {
+            Some((value, source)) => value.parse().unwrap_or_else(|e| {
+                warning!("ignoring {source}: {e}");
+                ListFormat::Table
+            }),
+            None => ListFormat::Table,
+        }
+    }
 }